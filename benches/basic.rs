@@ -1,4 +1,5 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
 use glm::{look_at, perspective, vec3};
 use nalgebra_glm as glm;
 use vectorfoil::Renderer;
@@ -1,9 +1,8 @@
 use glm::{look_at, perspective, vec3};
 use nalgebra_glm as glm;
-use vectorfoil::{Renderer, SvgOptions};
+use vectorfoil::{Fit, Margins, Page, Renderer, SvgOptions, Unit};
 
 fn main() -> std::io::Result<()> {
-    let dpi = 72.0;
     let width = 10.0;
     let height = 10.0;
 
@@ -70,7 +69,16 @@ fn main() -> std::io::Result<()> {
     ]);
 
     let rp = renderer.render();
-    let opt = SvgOptions { width: width * dpi, height: height * dpi, by_layer: true };
+    let opt = SvgOptions {
+        by_layer: true,
+        ..SvgOptions::with_page(Page {
+            margins: Margins::uniform(0.5),
+            fit: Fit::Contain {
+                aspect: width / height,
+            },
+            ..Page::new(width, height, Unit::In)
+        })
+    };
 
     let d = rp.visible_only().as_standalone_svg(&opt);
 
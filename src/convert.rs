@@ -0,0 +1,214 @@
+//! Conversions from the vector and matrix types accepted as input,
+//! and into the point types returned as output.
+//!
+//! All of the geometry and camera input of the `Renderer` is generic
+//! over these traits, so that `f32` (or other) pipelines can feed
+//! vectorfoil directly. All internal computation is done in `f64`.
+//!
+//! The `mint`, `glam` and `cgmath` features implement the traits for
+//! the vector and matrix types of those crates.
+
+use crate::common::*;
+use na::{Matrix4, Point3, Scalar};
+
+/// A 3d point or vector that can be converted into a `DVec3`.
+pub trait IntoDVec3 {
+    fn into_dvec3(self) -> DVec3;
+}
+
+/// A 4x4 matrix that can be converted into a `Matrix4<f64>`.
+pub trait ToDMat4 {
+    fn to_dmat4(&self) -> Matrix4<f64>;
+}
+
+impl<T: Scalar + Into<f64>> IntoDVec3 for glm::TVec3<T> {
+    fn into_dvec3(self) -> DVec3 {
+        self.map(|x| x.into())
+    }
+}
+
+impl<T: Scalar + Into<f64>> IntoDVec3 for &glm::TVec3<T> {
+    fn into_dvec3(self) -> DVec3 {
+        self.map(|x| x.into())
+    }
+}
+
+impl<T: Scalar + Into<f64>> IntoDVec3 for Point3<T> {
+    fn into_dvec3(self) -> DVec3 {
+        self.coords.into_dvec3()
+    }
+}
+
+impl<T: Into<f64>> IntoDVec3 for [T; 3] {
+    fn into_dvec3(self) -> DVec3 {
+        let [x, y, z] = self;
+        vec3(x.into(), y.into(), z.into())
+    }
+}
+
+impl<T: Scalar + Into<f64>> ToDMat4 for Matrix4<T> {
+    fn to_dmat4(&self) -> Matrix4<f64> {
+        self.map(|x| x.into())
+    }
+}
+
+/// A 2d point type that output points can be converted into.
+pub trait FromDVec2 {
+    fn from_dvec2(p: DVec2) -> Self;
+}
+
+impl FromDVec2 for DVec2 {
+    fn from_dvec2(p: DVec2) -> Self {
+        p
+    }
+}
+
+impl FromDVec2 for glm::Vec2 {
+    fn from_dvec2(p: DVec2) -> Self {
+        glm::vec2(p.x as f32, p.y as f32)
+    }
+}
+
+impl FromDVec2 for na::Point2<f64> {
+    fn from_dvec2(p: DVec2) -> Self {
+        p.into()
+    }
+}
+
+impl FromDVec2 for [f64; 2] {
+    fn from_dvec2(p: DVec2) -> Self {
+        [p.x, p.y]
+    }
+}
+
+#[cfg(feature = "mint")]
+mod mint_impls {
+    use super::*;
+
+    impl<T: Into<f64>> IntoDVec3 for mint::Vector3<T> {
+        fn into_dvec3(self) -> DVec3 {
+            vec3(self.x.into(), self.y.into(), self.z.into())
+        }
+    }
+
+    impl<T: Into<f64>> IntoDVec3 for mint::Point3<T> {
+        fn into_dvec3(self) -> DVec3 {
+            vec3(self.x.into(), self.y.into(), self.z.into())
+        }
+    }
+
+    impl<T: Into<f64> + Copy> ToDMat4 for mint::ColumnMatrix4<T> {
+        fn to_dmat4(&self) -> Matrix4<f64> {
+            let c = [self.x, self.y, self.z, self.w];
+            Matrix4::from_fn(|r, i| {
+                let col = c[i];
+                [col.x, col.y, col.z, col.w][r].into()
+            })
+        }
+    }
+
+    impl<T: Into<f64> + Copy> ToDMat4 for mint::RowMatrix4<T> {
+        fn to_dmat4(&self) -> Matrix4<f64> {
+            let r = [self.x, self.y, self.z, self.w];
+            Matrix4::from_fn(|i, c| {
+                let row = r[i];
+                [row.x, row.y, row.z, row.w][c].into()
+            })
+        }
+    }
+
+    impl FromDVec2 for mint::Vector2<f64> {
+        fn from_dvec2(p: DVec2) -> Self {
+            mint::Vector2 { x: p.x, y: p.y }
+        }
+    }
+
+    impl FromDVec2 for mint::Point2<f64> {
+        fn from_dvec2(p: DVec2) -> Self {
+            mint::Point2 { x: p.x, y: p.y }
+        }
+    }
+}
+
+#[cfg(feature = "glam")]
+mod glam_impls {
+    use super::*;
+
+    impl IntoDVec3 for glam::Vec3 {
+        fn into_dvec3(self) -> DVec3 {
+            vec3(self.x.into(), self.y.into(), self.z.into())
+        }
+    }
+
+    impl IntoDVec3 for glam::Vec3A {
+        fn into_dvec3(self) -> DVec3 {
+            vec3(self.x.into(), self.y.into(), self.z.into())
+        }
+    }
+
+    impl IntoDVec3 for glam::DVec3 {
+        fn into_dvec3(self) -> DVec3 {
+            vec3(self.x, self.y, self.z)
+        }
+    }
+
+    impl ToDMat4 for glam::Mat4 {
+        fn to_dmat4(&self) -> Matrix4<f64> {
+            Matrix4::from_column_slice(&self.to_cols_array()).map(|x| x.into())
+        }
+    }
+
+    impl ToDMat4 for glam::DMat4 {
+        fn to_dmat4(&self) -> Matrix4<f64> {
+            Matrix4::from_column_slice(&self.to_cols_array())
+        }
+    }
+
+    impl FromDVec2 for glam::Vec2 {
+        fn from_dvec2(p: DVec2) -> Self {
+            glam::Vec2::new(p.x as f32, p.y as f32)
+        }
+    }
+
+    impl FromDVec2 for glam::DVec2 {
+        fn from_dvec2(p: DVec2) -> Self {
+            glam::DVec2::new(p.x, p.y)
+        }
+    }
+}
+
+#[cfg(feature = "cgmath")]
+mod cgmath_impls {
+    use super::*;
+
+    impl<T: Into<f64>> IntoDVec3 for cgmath::Vector3<T> {
+        fn into_dvec3(self) -> DVec3 {
+            vec3(self.x.into(), self.y.into(), self.z.into())
+        }
+    }
+
+    impl<T: Into<f64>> IntoDVec3 for cgmath::Point3<T> {
+        fn into_dvec3(self) -> DVec3 {
+            vec3(self.x.into(), self.y.into(), self.z.into())
+        }
+    }
+
+    impl<T: Into<f64> + Copy> ToDMat4 for cgmath::Matrix4<T> {
+        fn to_dmat4(&self) -> Matrix4<f64> {
+            // cgmath matrices are indexed by column first.
+            Matrix4::from_fn(|r, c| self[c][r].into())
+        }
+    }
+
+    impl FromDVec2 for cgmath::Vector2<f64> {
+        fn from_dvec2(p: DVec2) -> Self {
+            cgmath::Vector2::new(p.x, p.y)
+        }
+    }
+
+    impl FromDVec2 for cgmath::Point2<f64> {
+        fn from_dvec2(p: DVec2) -> Self {
+            cgmath::Point2::new(p.x, p.y)
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Removal of coincident and overlapping lines.
+//!
+//! Every triangle contributes all three of its edges, so an edge
+//! shared by two neighbouring triangles comes out twice. Drawing both
+//! copies makes a plotter trace the edge twice.
+
+use crate::common::*;
+use crate::intersect::perspective_lerp;
+use crate::render_paths::RenderLine;
+use std::collections::HashMap;
+
+/// Default precedence of the edge types, from highest to lowest.
+pub const DEFAULT_PRECEDENCE: [EdgeType; 6] = [
+    EdgeType::Visible,
+    EdgeType::Hatch,
+    EdgeType::Hidden,
+    EdgeType::Split,
+    EdgeType::Invisible,
+    EdgeType::Culled,
+];
+
+/// Rank of `edge` in `precedence`; lower ranks win. Edge types not
+/// listed rank last.
+fn rank(precedence: &[EdgeType], edge: EdgeType) -> usize {
+    precedence
+        .iter()
+        .position(|e| *e == edge)
+        .unwrap_or(precedence.len())
+}
+
+/// Width of the direction bins, in radians.
+const ANGLE_BIN: f64 = 0.01;
+
+/// Lines binned by direction and offset, so that only the lines in
+/// neighbouring bins need to be compared. Lines too short for their
+/// direction to be reliable are kept apart and compared with all
+/// others.
+struct LineBins {
+    bins: HashMap<(i64, i64), Vec<usize>>,
+    short: Vec<usize>,
+    angle_bins: i64,
+    offset_bin: f64,
+    min_length: f64,
+}
+
+impl LineBins {
+    fn new(lines: &[RenderLine], tolerance: f64) -> LineBins {
+        let radius = lines
+            .iter()
+            .flat_map(|l| l.points().to_vec())
+            .map(|p| p.norm())
+            .fold(0.0, f64::max);
+        let mut bins = LineBins {
+            bins: HashMap::new(),
+            short: vec![],
+            angle_bins: (std::f64::consts::PI / ANGLE_BIN).ceil() as i64,
+            // Two lines on the same line (within `tolerance`) differ
+            // in direction by less than ANGLE_BIN, and so in offset by
+            // less than this.
+            offset_bin: (tolerance + ANGLE_BIN * radius).max(f64::EPSILON),
+            min_length: 4.0 * tolerance / ANGLE_BIN,
+        };
+        for (i, l) in lines.iter().enumerate() {
+            match bins.key(l) {
+                Some(k) => bins.bins.entry(k).or_default().push(i),
+                None => bins.short.push(i),
+            }
+        }
+        bins
+    }
+
+    /// Direction and offset bins of the infinite line through `line`,
+    /// or `None` if the line is too short.
+    fn key(&self, line: &RenderLine) -> Option<(i64, i64)> {
+        let [a, b] = line.points();
+        let d = b - a;
+        if d.norm() < self.min_length {
+            return None;
+        }
+        // direction in [0, pi), with the offset along its normal
+        let d = if d.y < 0.0 || (d.y == 0.0 && d.x < 0.0) { -d } else { d };
+        let angle = d.y.atan2(d.x);
+        let offset = (d.x * a.y - d.y * a.x) / d.norm();
+        Some((
+            ((angle / ANGLE_BIN).floor() as i64).min(self.angle_bins - 1),
+            (offset / self.offset_bin).floor() as i64,
+        ))
+    }
+
+    /// Indices of the lines that may lie on the same infinite line as
+    /// `line`, in no particular order.
+    fn candidates(&self, line: &RenderLine, count: usize) -> Vec<usize> {
+        let (angle, offset) = match self.key(line) {
+            Some(k) => k,
+            None => return (0..count).collect(),
+        };
+        let mut out = self.short.clone();
+        for da in -1..=1 {
+            // Directions wrap around at pi, which flips the normal
+            // and so the sign of the offset.
+            let a = angle + da;
+            let (a, o) = if a < 0 {
+                (a + self.angle_bins, -offset - 1)
+            } else if a >= self.angle_bins {
+                (a - self.angle_bins, -offset - 1)
+            } else {
+                (a, offset)
+            };
+            for o in o - 1..=o + 1 {
+                out.extend(self.bins.get(&(a, o)).into_iter().flatten());
+            }
+        }
+        out
+    }
+}
+
+/// Remove the overlap between collinear lines (within `tolerance`).
+/// Where lines overlap, the one whose edge type comes first in
+/// `precedence` is kept, and the others are cut away. Of lines with
+/// the same edge type, the first one is kept.
+pub fn dedup_lines(
+    lines: &[RenderLine],
+    tolerance: f64,
+    precedence: &[EdgeType],
+) -> Vec<RenderLine> {
+    let bins = LineBins::new(lines, tolerance);
+    let mut out = vec![];
+    let mut assigned = vec![false; lines.len()];
+    for i in 0..lines.len() {
+        if assigned[i] {
+            continue;
+        }
+        let [o, b] = lines[i].points();
+        let len = (b - o).norm();
+        if len <= tolerance {
+            // too short to have a direction
+            assigned[i] = true;
+            out.push(lines[i]);
+            continue;
+        }
+        let d = (b - o) / len;
+        let off = |p: DVec2| {
+            let v = p - o;
+            (d.x * v.y - d.y * v.x).abs()
+        };
+
+        // all of the lines on the same infinite line as line i
+        let mut candidates = bins.candidates(&lines[i], lines.len());
+        candidates.sort_unstable();
+        candidates.dedup();
+        let mut cluster = vec![];
+        for j in candidates.into_iter().filter(|j| *j >= i) {
+            let [p0, p1] = lines[j].points();
+            if !assigned[j] && off(p0) <= tolerance && off(p1) <= tolerance {
+                assigned[j] = true;
+                let (t0, t1) = ((p0 - o).dot(&d), (p1 - o).dot(&d));
+                cluster.push((j, t0.min(t1), t0.max(t1)));
+            }
+        }
+        if cluster.len() == 1 {
+            out.push(lines[i]);
+            continue;
+        }
+
+        let mut breaks: Vec<f64> = cluster.iter().flat_map(|(_, t0, t1)| [*t0, *t1]).collect();
+        breaks.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        breaks.dedup_by(|b, a| *b - *a <= tolerance);
+
+        // pieces between the breaks, each with the line covering it
+        // that wins
+        let mut pieces: Vec<(usize, f64, f64)> = vec![];
+        for w in breaks.windows(2) {
+            let mid = (w[0] + w[1]) * 0.5;
+            let winner = cluster
+                .iter()
+                .filter(|(_, t0, t1)| *t0 - tolerance <= mid && mid <= *t1 + tolerance)
+                .min_by_key(|(j, ..)| (rank(precedence, lines[*j].edge()), *j));
+            if let Some(&(j, ..)) = winner {
+                match pieces.last_mut() {
+                    Some(last) if last.0 == j => last.2 = w[1],
+                    _ => pieces.push((j, w[0], w[1])),
+                }
+            }
+        }
+
+        for (j, t0, t1) in pieces {
+            let line = &lines[j];
+            let [p0, p1] = line.ndc_points();
+            let (s0, s1) = ((p0.xy() - o).dot(&d), (p1.xy() - o).dot(&d));
+            // t is linear in screen space, so w is not
+            let at = |t: f64| {
+                let s = if (s1 - s0).abs() > 0.0 {
+                    (t - s0) / (s1 - s0)
+                } else {
+                    0.0
+                };
+                perspective_lerp(s, p0, p1)
+            };
+            // keep the direction of the original line
+            let (a, b) = if s0 <= s1 {
+                (at(t0), at(t1))
+            } else {
+                (at(t1), at(t0))
+            };
+            out.push(line.with_points(a, b));
+        }
+    }
+    out
+}
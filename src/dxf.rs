@@ -0,0 +1,198 @@
+//! DXF output, for importing rendered views into CAD as 2D drawings.
+//!
+//! The files are written as DXF R12 (`AC1009`), the most widely read
+//! version, which needs no entity handles or subclass markers.
+
+use crate::common::*;
+use crate::page::{Page, Unit};
+use crate::polyline::RenderPolyline;
+use crate::render_paths::RenderPaths;
+use std::fmt::Display;
+use std::io::{self, Write};
+
+/// Options for `RenderPaths::write_dxf`.
+#[derive(Debug, Clone)]
+pub struct DxfOptions {
+    /// Page the drawing is laid out on. The drawing origin is the
+    /// bottom left corner of the page.
+    pub page: Page,
+
+    /// Unit of the drawing, recorded in the header as `$INSUNITS`.
+    /// R12 has no unit of its own; readers that predate the variable
+    /// ignore it.
+    pub unit: Unit,
+
+    /// Length of the dashes and gaps of the `DASHED` linetype, in
+    /// millimetres.
+    pub dash: (f64, f64),
+}
+
+impl DxfOptions {
+    pub fn new(page: Page) -> DxfOptions {
+        DxfOptions {
+            page,
+            unit: Unit::Mm,
+            dash: (3.0, 1.5),
+        }
+    }
+
+    /// Map a point from NDC to drawing coordinates.
+    pub fn to_drawing(&self, p: DVec2) -> DVec2 {
+        self.page.to_y_up(p, self.unit)
+    }
+
+    /// DXF layer name of the edge type.
+    pub fn layer_name(edge: EdgeType) -> String {
+        edge.class_name().to_uppercase()
+    }
+
+    /// DXF layer name of the points.
+    pub const POINT_LAYER: &'static str = "POINTS";
+
+    /// Linetype and colour index of the layer of the edge type.
+    /// Hidden edges are dashed, all others are continuous.
+    fn layer_style(edge: EdgeType) -> (&'static str, u32) {
+        match edge {
+            EdgeType::Visible | EdgeType::Hatch => ("CONTINUOUS", 7),
+            EdgeType::Hidden => ("DASHED", 5),
+            EdgeType::Invisible => ("CONTINUOUS", 8),
+            EdgeType::Split => ("CONTINUOUS", 3),
+            EdgeType::Culled => ("CONTINUOUS", 1),
+        }
+    }
+}
+
+fn group<W: Write>(w: &mut W, code: u32, value: impl Display) -> io::Result<()> {
+    writeln!(w, "{}\n{}", code, value)
+}
+
+fn insunits(unit: Unit) -> u32 {
+    match unit {
+        Unit::Px => 0,
+        Unit::In => 1,
+        Unit::Mm => 4,
+    }
+}
+
+impl RenderPaths {
+    /// Write the lines and polylines as DXF `LINE` and `POLYLINE`
+    /// entities, with one layer per edge type, and the points as
+    /// `POINT` entities on the `POINTS` layer.
+    pub fn write_dxf<W: Write>(&self, w: &mut W, options: &DxfOptions) -> io::Result<()> {
+        let strokes: Vec<RenderPolyline> = self
+            .strokes()
+            .into_iter()
+            .filter(|s| s.points.len() >= 2)
+            .collect();
+        let edges: Vec<EdgeType> = EdgeType::ALL
+            .iter()
+            .copied()
+            .filter(|e| strokes.iter().any(|s| s.edge == *e))
+            .collect();
+
+        group(w, 0, "SECTION")?;
+        group(w, 2, "HEADER")?;
+        group(w, 9, "$ACADVER")?;
+        group(w, 1, "AC1009")?;
+        group(w, 9, "$INSUNITS")?;
+        group(w, 70, insunits(options.unit))?;
+        group(w, 0, "ENDSEC")?;
+
+        group(w, 0, "SECTION")?;
+        group(w, 2, "TABLES")?;
+        group(w, 0, "TABLE")?;
+        group(w, 2, "LTYPE")?;
+        group(w, 70, 2)?;
+        group(w, 0, "LTYPE")?;
+        group(w, 2, "CONTINUOUS")?;
+        group(w, 70, 0)?;
+        group(w, 3, "Solid line")?;
+        group(w, 72, 65)?;
+        group(w, 73, 0)?;
+        group(w, 40, 0.0)?;
+        let on = Unit::Mm.convert(options.dash.0, options.unit);
+        let off = Unit::Mm.convert(options.dash.1, options.unit);
+        group(w, 0, "LTYPE")?;
+        group(w, 2, "DASHED")?;
+        group(w, 70, 0)?;
+        group(w, 3, "Dashed line")?;
+        group(w, 72, 65)?;
+        group(w, 73, 2)?;
+        group(w, 40, on + off)?;
+        group(w, 49, on)?;
+        group(w, 49, -off)?;
+        group(w, 0, "ENDTAB")?;
+
+        group(w, 0, "TABLE")?;
+        group(w, 2, "LAYER")?;
+        // Layer "0" always exists in CAD programs, and some readers
+        // reject files that do not declare it.
+        let mut layers = vec![("0".to_string(), ("CONTINUOUS", 7))];
+        layers.extend(
+            edges
+                .iter()
+                .map(|e| (DxfOptions::layer_name(*e), DxfOptions::layer_style(*e))),
+        );
+        if !self.points.is_empty() {
+            layers.push((DxfOptions::POINT_LAYER.to_string(), ("CONTINUOUS", 7)));
+        }
+        group(w, 70, layers.len())?;
+        for (name, (linetype, color)) in layers {
+            group(w, 0, "LAYER")?;
+            group(w, 2, name)?;
+            group(w, 70, 0)?;
+            group(w, 62, color)?;
+            group(w, 6, linetype)?;
+        }
+        group(w, 0, "ENDTAB")?;
+        group(w, 0, "ENDSEC")?;
+
+        group(w, 0, "SECTION")?;
+        group(w, 2, "ENTITIES")?;
+        for s in &strokes {
+            let layer = DxfOptions::layer_name(s.edge);
+            let points: Vec<DVec2> = s.points.iter().map(|p| options.to_drawing(*p)).collect();
+            if points.len() == 2 {
+                group(w, 0, "LINE")?;
+                group(w, 8, layer)?;
+                group(w, 10, points[0].x)?;
+                group(w, 20, points[0].y)?;
+                group(w, 30, 0.0)?;
+                group(w, 11, points[1].x)?;
+                group(w, 21, points[1].y)?;
+                group(w, 31, 0.0)?;
+            } else {
+                // A closed polyline repeats its first point; DXF
+                // closes it with a flag instead.
+                let closed = s.is_closed();
+                let points = if closed { &points[..points.len() - 1] } else { &points[..] };
+                group(w, 0, "POLYLINE")?;
+                group(w, 8, &layer)?;
+                group(w, 66, 1)?;
+                group(w, 10, 0.0)?;
+                group(w, 20, 0.0)?;
+                group(w, 30, 0.0)?;
+                group(w, 70, if closed { 1 } else { 0 })?;
+                for p in points {
+                    group(w, 0, "VERTEX")?;
+                    group(w, 8, &layer)?;
+                    group(w, 10, p.x)?;
+                    group(w, 20, p.y)?;
+                    group(w, 30, 0.0)?;
+                }
+                group(w, 0, "SEQEND")?;
+                group(w, 8, &layer)?;
+            }
+        }
+        for p in &self.points {
+            let p = options.to_drawing(p.point());
+            group(w, 0, "POINT")?;
+            group(w, 8, DxfOptions::POINT_LAYER)?;
+            group(w, 10, p.x)?;
+            group(w, 20, p.y)?;
+            group(w, 30, 0.0)?;
+        }
+        group(w, 0, "ENDSEC")?;
+        group(w, 0, "EOF")
+    }
+}
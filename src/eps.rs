@@ -0,0 +1,160 @@
+//! Encapsulated PostScript output, for LaTeX and other publishing
+//! tools.
+
+use crate::common::*;
+use crate::page::Unit;
+use crate::pdf::{cap_join, num, rgb, PT_PER_INCH};
+use crate::render_paths::{Marker, RenderPaths, SvgOptions};
+use crate::style::LineStyle;
+use std::io::{self, Write};
+
+/// PostScript operators setting the stroke style. Lengths are
+/// converted from page units by `scale`.
+fn stroke_state(style: &LineStyle, scale: f64) -> String {
+    let dash: Vec<String> = style.dash.iter().map(|d| num(d * scale)).collect();
+    let (cap, join) = cap_join(style);
+    format!(
+        "{} setrgbcolor {} setlinewidth [{}] 0 setdash {} setlinecap {} setlinejoin\n",
+        rgb(style.stroke),
+        num(style.width * scale),
+        dash.join(" "),
+        cap,
+        join
+    )
+}
+
+impl RenderPaths {
+    /// Write the faces, lines and points as an EPS file, with the
+    /// bounding box of the drawn lines, markers (and faces, if drawn)
+    /// including the line widths.
+    pub fn write_eps<W: Write>(&self, w: &mut W, options: &SvgOptions) -> io::Result<()> {
+        let page = &options.page;
+        let scale = page.unit.convert(1.0, Unit::In) * PT_PER_INCH;
+        let to_pt = |p: &DVec2| page.to_y_up(*p, Unit::In) * PT_PER_INCH;
+        let pt = |p: &DVec2| {
+            let q = to_pt(p);
+            format!("{} {}", num(q.x), num(q.y))
+        };
+
+        let mut lo = vec2(f64::INFINITY, f64::INFINITY);
+        let mut hi = -lo;
+        let mut extend = |p: DVec2, r: f64| {
+            lo = glm::min2(&lo, &p.add_scalar(-r));
+            hi = glm::max2(&hi, &p.add_scalar(r));
+        };
+
+        let mut body = String::new();
+        if options.faces {
+            for face in &self.faces {
+                let fill = self.face_color(face, options).unwrap_or(options.face_fill);
+                body += &format!("{} setrgbcolor\nnewpath\n", rgb(fill));
+                for l in face.loops.iter().filter(|l| !l.is_empty()) {
+                    body += &format!("{} moveto\n", pt(&l[0]));
+                    for p in &l[1..] {
+                        body += &format!("{} lineto\n", pt(p));
+                    }
+                    body += "closepath\n";
+                    l.iter().for_each(|p| extend(to_pt(p), 0.0));
+                }
+                body += "eofill\n";
+            }
+        }
+
+        let mut state = String::new();
+        for s in self.strokes_in_paint_order(options) {
+            if s.points.len() < 2 {
+                continue;
+            }
+            let style = self.stroke_style(&s, options);
+            let next = stroke_state(&style, scale);
+            if next != state {
+                body += &next;
+                state = next;
+            }
+            body += &format!("newpath\n{} moveto\n", pt(&s.points[0]));
+            for p in &s.points[1..] {
+                body += &format!("{} lineto\n", pt(p));
+            }
+            body += "stroke\n";
+            s.points
+                .iter()
+                .for_each(|p| extend(to_pt(p), style.width * scale / 2.0));
+        }
+
+        if !self.points.is_empty() {
+            let markers = &options.markers;
+            // a cross reaches half its stroke width past its corners
+            let pad = match markers.shape {
+                Marker::Cross => {
+                    body += &format!(
+                        "{} setrgbcolor {} setlinewidth [] 0 setdash 0 setlinecap 0 setlinejoin\n",
+                        rgb(markers.color),
+                        num(markers.stroke_width * scale)
+                    );
+                    markers.stroke_width * scale / 2.0
+                }
+                _ => {
+                    body += &format!("{} setrgbcolor\n", rgb(markers.color));
+                    0.0
+                }
+            };
+            for point in &self.points {
+                let c = to_pt(&point.point());
+                let r = markers.point_size(point) * scale;
+                body += &match markers.shape {
+                    Marker::Circle => format!(
+                        "newpath\n{} {} {} 0 360 arc\nclosepath\nfill\n",
+                        num(c.x),
+                        num(c.y),
+                        num(r)
+                    ),
+                    Marker::Square => format!(
+                        "{} {} {} {} rectfill\n",
+                        num(c.x - r),
+                        num(c.y - r),
+                        num(2.0 * r),
+                        num(2.0 * r)
+                    ),
+                    Marker::Cross => format!(
+                        "newpath\n{} {} moveto\n{} {} lineto\n{} {} moveto\n{} {} lineto\nstroke\n",
+                        num(c.x - r),
+                        num(c.y - r),
+                        num(c.x + r),
+                        num(c.y + r),
+                        num(c.x - r),
+                        num(c.y + r),
+                        num(c.x + r),
+                        num(c.y - r)
+                    ),
+                };
+                extend(c, r + pad);
+            }
+        }
+
+        if lo.x > hi.x {
+            lo = vec2(0.0, 0.0);
+            hi = lo;
+        }
+        writeln!(w, "%!PS-Adobe-3.0 EPSF-3.0")?;
+        writeln!(
+            w,
+            "%%BoundingBox: {} {} {} {}",
+            num(lo.x.floor()),
+            num(lo.y.floor()),
+            num(hi.x.ceil()),
+            num(hi.y.ceil())
+        )?;
+        writeln!(
+            w,
+            "%%HiResBoundingBox: {} {} {} {}",
+            num(lo.x),
+            num(lo.y),
+            num(hi.x),
+            num(hi.y)
+        )?;
+        writeln!(w, "%%EndComments")?;
+        write!(w, "{}", body)?;
+        writeln!(w, "showpage")?;
+        writeln!(w, "%%EOF")
+    }
+}
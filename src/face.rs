@@ -0,0 +1,250 @@
+//! Visible faces, reconstructed from the triangle fragments that are
+//! left after hidden surface removal.
+//!
+//! The `Renderer` splits source triangles and polygons into many
+//! fragments. The fragments of a single source face are merged back
+//! into outline loops by cancelling the edges they share.
+
+use crate::common::*;
+use crate::intersect::is_degen_tri;
+use crate::primitive::{ObjectId, PrimId, ZsortPrim};
+use std::collections::HashMap;
+
+/// The visible region of a single source face.
+#[derive(Debug, Clone)]
+pub struct RenderFace {
+    pub(crate) loops: Vec<Vec<DVec2>>,
+    pub(crate) depth: f64,
+    pub(crate) attr: Option<usize>,
+    pub(crate) id: Option<PrimId>,
+    pub(crate) object: Option<ObjectId>,
+    pub(crate) normal: Option<DVec3>,
+}
+
+impl RenderFace {
+    /// Create a face from the (non-hidden) triangle fragments of a
+    /// single source face. Returns `None` if nothing is left of the
+    /// face.
+    pub(crate) fn from_fragments(fragments: &[&ZsortPrim]) -> Option<RenderFace> {
+        let tris: Vec<[DVec4; 3]> = fragments
+            .iter()
+            .filter_map(|zp| match &zp.p {
+                Primitive::Triangle { tri } => Some(tri.p),
+                _ => None,
+            })
+            .collect();
+        let first = fragments.first()?;
+
+        // area-weighted depth of the fragments
+        let (mut area, mut depth) = (0.0, 0.0);
+        for p in &tris {
+            let a = signed_area(&[p[0].xy(), p[1].xy(), p[2].xy()]).abs();
+            area += a;
+            depth += a * (p[0].z + p[1].z + p[2].z) / 3.0;
+        }
+        if area <= 0.0 {
+            return None;
+        }
+
+        let loops = merge_triangles(
+            &tris
+                .iter()
+                .map(|p| [p[0].xy(), p[1].xy(), p[2].xy()])
+                .collect::<Vec<_>>(),
+        );
+        if loops.is_empty() {
+            return None;
+        }
+        Some(RenderFace {
+            loops,
+            depth: depth / area,
+            attr: first.attr,
+            id: first.id,
+            object: first.object,
+            normal: first.normal,
+        })
+    }
+
+    /// Outline loops of the face, in NDC. Outer boundaries are
+    /// counter-clockwise, and holes are clockwise.
+    pub fn loops(&self) -> &[Vec<DVec2>] {
+        &self.loops
+    }
+
+    /// Mean NDC depth (z/w) of the visible part of the face.
+    pub fn depth(&self) -> f64 {
+        self.depth
+    }
+
+    /// Index into `RenderPaths::attributes` of this face's attributes.
+    pub fn attr(&self) -> Option<usize> {
+        self.attr
+    }
+
+    /// Id of the source primitive of the face.
+    pub fn id(&self) -> Option<PrimId> {
+        self.id
+    }
+
+    /// Id of the object the face belongs to.
+    pub fn object(&self) -> Option<ObjectId> {
+        self.object
+    }
+
+    /// World-space unit normal of the source face, facing the eye.
+    pub fn normal(&self) -> Option<DVec3> {
+        self.normal
+    }
+
+    /// Return true iff `p` lies within the visible region of the
+    /// face.
+    pub fn contains(&self, p: DVec2) -> bool {
+        // count the crossings of a ray in the +x direction
+        let mut inside = false;
+        for l in &self.loops {
+            for i in 0..l.len() {
+                let (a, b) = (l[i], l[(i + 1) % l.len()]);
+                if (a.y > p.y) != (b.y > p.y) {
+                    let x = a.x + (b.x - a.x) * (p.y - a.y) / (b.y - a.y);
+                    if x > p.x {
+                        inside = !inside;
+                    }
+                }
+            }
+        }
+        inside
+    }
+
+    /// Visible 2d area of the face.
+    pub fn area(&self) -> f64 {
+        self.loops.iter().map(|l| signed_area(l)).sum()
+    }
+}
+
+/// Signed area of a closed polygon; positive if counter-clockwise.
+pub fn signed_area(points: &[DVec2]) -> f64 {
+    let n = points.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum::<f64>()
+        * 0.5
+}
+
+/// Merge a set of non-overlapping, counter-clockwise triangles into
+/// the loops outlining their union.
+///
+/// Edges shared between triangles (in opposite directions) cancel
+/// out. Edges are first split at any vertex lying on them, so that
+/// T-junctions left by the splitting also cancel.
+pub fn merge_triangles(tris: &[[DVec2; 3]]) -> Vec<Vec<DVec2>> {
+    // Weld the vertices together.
+    let mut verts: Vec<DVec2> = vec![];
+    let mut index = |p: DVec2| match verts.iter().position(|v| (v - p).norm() <= EPS) {
+        Some(i) => i,
+        None => {
+            verts.push(p);
+            verts.len() - 1
+        }
+    };
+    let tris: Vec<[usize; 3]> = tris
+        .iter()
+        .map(|t| [index(t[0]), index(t[1]), index(t[2])])
+        .collect();
+
+    // Split every edge at the vertices lying within it.
+    let mut edges: HashMap<(usize, usize), isize> = HashMap::new();
+    for t in &tris {
+        for i in 0..3 {
+            let (a, b) = (t[i], t[(i + 1) % 3]);
+            if a == b {
+                continue;
+            }
+            let (pa, pb) = (verts[a], verts[b]);
+            let d = pb - pa;
+            let len2 = d.norm_squared();
+            let mut inner: Vec<(f64, usize)> = verts
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != a && *j != b)
+                .filter_map(|(j, v)| {
+                    let s = (v - pa).dot(&d) / len2;
+                    let off = (v - (pa + d * s)).norm();
+                    if s > 0.0 && s < 1.0 && off <= EPS {
+                        Some((s, j))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            inner.sort_by(|x, y| x.0.partial_cmp(&y.0).unwrap());
+
+            let chain: Vec<usize> = std::iter::once(a)
+                .chain(inner.into_iter().map(|(_, j)| j))
+                .chain(std::iter::once(b))
+                .collect();
+            for w in chain.windows(2) {
+                // Count each directed edge, cancelling it against
+                // its reverse.
+                let rev = edges.entry((w[1], w[0])).or_insert(0);
+                if *rev > 0 {
+                    *rev -= 1;
+                } else {
+                    *edges.entry((w[0], w[1])).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    // Chain the remaining boundary edges into loops.
+    let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut boundary: Vec<(usize, usize)> = vec![];
+    for (e, n) in edges {
+        for _ in 0..n {
+            boundary.push(e);
+        }
+    }
+    boundary.sort_unstable();
+    for (a, b) in &boundary {
+        outgoing.entry(*a).or_default().push(*b);
+    }
+
+    let mut loops = vec![];
+    for (start, _) in boundary {
+        if !matches!(outgoing.get(&start), Some(v) if !v.is_empty()) {
+            continue;
+        }
+        let mut l = vec![start];
+        let mut cur = start;
+        while let Some(next) = outgoing.get_mut(&cur).and_then(|v| v.pop()) {
+            if next == start {
+                break;
+            }
+            l.push(next);
+            cur = next;
+        }
+        let l = simplify_loop(l.into_iter().map(|i| verts[i]).collect());
+        if l.len() >= 3 {
+            loops.push(l);
+        }
+    }
+    loops
+}
+
+/// Remove the collinear (and doubled-back) vertices of a loop.
+fn simplify_loop(mut l: Vec<DVec2>) -> Vec<DVec2> {
+    let mut i = 0;
+    while l.len() >= 3 && i < l.len() {
+        let n = l.len();
+        let (a, b, c) = (l[(i + n - 1) % n], l[i], l[(i + 1) % n]);
+        if is_degen_tri(a, b, c) {
+            l.remove(i);
+            i = i.saturating_sub(1);
+        } else {
+            i += 1;
+        }
+    }
+    l
+}
@@ -0,0 +1,168 @@
+//! G-code output, for GRBL-style pen plotters and laser engravers.
+
+use crate::common::*;
+use crate::page::{Page, Unit};
+use crate::polyline::RenderPolyline;
+use crate::render_paths::{LayerSplit, RenderPaths, RenderPoint, SvgOptions};
+use std::io::{self, Write};
+
+/// How the pen (or laser) is switched between drawing and moving.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PenLift {
+    /// Send these commands, such as servo or spindle M-codes.
+    Commands { up: String, down: String },
+
+    /// Move the Z axis to these heights, in millimetres.
+    Z { up: f64, down: f64 },
+}
+
+impl PenLift {
+    fn up(&self) -> String {
+        match self {
+            PenLift::Commands { up, .. } => up.clone(),
+            PenLift::Z { up, .. } => format!("G0 Z{:.3}", up),
+        }
+    }
+
+    fn down(&self) -> String {
+        match self {
+            PenLift::Commands { down, .. } => down.clone(),
+            PenLift::Z { down, .. } => format!("G0 Z{:.3}", down),
+        }
+    }
+}
+
+/// Where the machine origin is on the page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+    BottomLeft,
+
+    /// Top left, with the page in negative y.
+    TopLeft,
+
+    Center,
+}
+
+/// Options for `RenderPaths::write_gcode`.
+#[derive(Debug, Clone)]
+pub struct GcodeOptions {
+    /// Page the drawing is laid out on. Coordinates are written in
+    /// millimetres.
+    pub page: Page,
+
+    pub origin: Origin,
+
+    pub pen: PenLift,
+
+    /// Feed rate while drawing, in mm/min.
+    pub feed_rate: f64,
+
+    /// Feed rate while moving between strokes, in mm/min. If `None`,
+    /// moves are rapid (`G0`).
+    pub travel_rate: Option<f64>,
+}
+
+impl GcodeOptions {
+    pub fn new(page: Page) -> GcodeOptions {
+        GcodeOptions {
+            page,
+            origin: Origin::BottomLeft,
+            pen: PenLift::Commands {
+                up: "M3 S0".to_string(),
+                down: "M3 S1000".to_string(),
+            },
+            feed_rate: 1000.0,
+            travel_rate: None,
+        }
+    }
+
+    /// Map a point from NDC to machine coordinates, in millimetres.
+    pub fn to_machine(&self, p: DVec2) -> DVec2 {
+        let q = self.page.to_y_up(p, Unit::Mm);
+        let size = vec2(
+            self.page.unit.convert(self.page.width, Unit::Mm),
+            self.page.unit.convert(self.page.height, Unit::Mm),
+        );
+        match self.origin {
+            Origin::BottomLeft => q,
+            Origin::TopLeft => q - vec2(0.0, size.y),
+            Origin::Center => q - size * 0.5,
+        }
+    }
+
+    fn travel<W: Write>(&self, w: &mut W, p: DVec2) -> io::Result<()> {
+        let p = self.to_machine(p);
+        match self.travel_rate {
+            Some(f) => writeln!(w, "G1 X{:.3} Y{:.3} F{}", p.x, p.y, f),
+            None => writeln!(w, "G0 X{:.3} Y{:.3}", p.x, p.y),
+        }
+    }
+
+    fn write<'a, W: Write>(
+        &self,
+        w: &mut W,
+        strokes: impl Iterator<Item = &'a RenderPolyline>,
+        points: &[RenderPoint],
+    ) -> io::Result<()> {
+        writeln!(w, "G21")?;
+        writeln!(w, "G90")?;
+        writeln!(w, "{}", self.pen.up())?;
+        for s in strokes.filter(|s| s.points.len() >= 2) {
+            self.travel(w, s.points[0])?;
+            writeln!(w, "{}", self.pen.down())?;
+            for (i, p) in s.points[1..].iter().enumerate() {
+                let p = self.to_machine(*p);
+                if i == 0 {
+                    writeln!(w, "G1 X{:.3} Y{:.3} F{}", p.x, p.y, self.feed_rate)?;
+                } else {
+                    writeln!(w, "G1 X{:.3} Y{:.3}", p.x, p.y)?;
+                }
+            }
+            writeln!(w, "{}", self.pen.up())?;
+        }
+        // a plunge and lift in place for each point
+        for p in points {
+            self.travel(w, p.point())?;
+            writeln!(w, "{}", self.pen.down())?;
+            writeln!(w, "{}", self.pen.up())?;
+        }
+        writeln!(w, "G0 X0 Y0")
+    }
+}
+
+impl RenderPaths {
+    /// Write the lines and polylines as G-code, in their current
+    /// order, followed by the points as pen dots. See
+    /// `optimize_travel` to reduce the pen-up moves.
+    pub fn write_gcode<W: Write>(&self, w: &mut W, options: &GcodeOptions) -> io::Result<()> {
+        options.write(w, self.strokes().iter(), &self.points)
+    }
+
+    /// Write one G-code program per layer, split as given, with the
+    /// layer names and order of `layers` (see `line_layers`). The
+    /// points, if any, go into a last program named "points".
+    ///
+    /// `open` is called with the name of each layer, and returns the
+    /// writer for it. The writers are returned, by layer name, once
+    /// written.
+    pub fn write_gcode_layers<W: Write>(
+        &self,
+        options: &GcodeOptions,
+        layers: &SvgOptions,
+        split: LayerSplit,
+        mut open: impl FnMut(&str) -> io::Result<W>,
+    ) -> io::Result<Vec<(String, W)>> {
+        let mut written = vec![];
+        for (name, strokes) in self.line_layers(split, layers) {
+            let mut w = open(&name)?;
+            options.write(&mut w, strokes.iter(), &[])?;
+            written.push((name, w));
+        }
+        if !self.points.is_empty() {
+            let mut w = open("points")?;
+            options.write(&mut w, std::iter::empty(), &self.points)?;
+            written.push(("points".to_string(), w));
+        }
+        Ok(written)
+    }
+}
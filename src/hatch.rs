@@ -0,0 +1,137 @@
+//! Hatching of the visible faces with parallel lines, for output
+//! devices (such as pen plotters) that cannot fill regions.
+//!
+//! The spacing of the hatch lines follows the tone of each face: the
+//! `tone` attribute of the face if set, or else its brightness under
+//! a `Light`.
+
+use crate::common::*;
+use crate::face::RenderFace;
+use crate::shade::{face_tone, Light};
+use crate::style::Attributes;
+
+/// Options for `RenderPaths::add_hatching`.
+#[derive(Debug, Clone, Copy)]
+pub struct HatchOptions {
+    /// Angle of the hatch lines, in radians counter-clockwise from the
+    /// x axis.
+    pub angle: f64,
+
+    /// Spacing of the hatch lines of a black face, in NDC units.
+    pub min_spacing: f64,
+
+    /// Faces whose hatch lines would be further apart than this are
+    /// left empty.
+    pub max_spacing: f64,
+
+    /// Faces with a tone below this are also hatched perpendicular to
+    /// `angle`.
+    pub cross_hatch: Option<f64>,
+
+    /// Light used for the tone of faces without a `tone` attribute.
+    /// Faces without either are not hatched.
+    pub light: Option<Light>,
+}
+
+impl HatchOptions {
+    pub fn new(min_spacing: f64) -> HatchOptions {
+        HatchOptions {
+            angle: std::f64::consts::FRAC_PI_4,
+            min_spacing,
+            max_spacing: min_spacing * 8.0,
+            cross_hatch: None,
+            light: None,
+        }
+    }
+
+    /// Spacing of the hatch lines for a face of tone `tone`, or `None`
+    /// if the face is too light to be hatched.
+    pub fn spacing(&self, tone: f64) -> Option<f64> {
+        let darkness = 1.0 - tone;
+        if darkness <= 0.0 {
+            return None;
+        }
+        let spacing = self.min_spacing / darkness;
+        if spacing > self.max_spacing {
+            None
+        } else {
+            Some(spacing)
+        }
+    }
+
+    /// Hatch lines of `face`, given its attributes.
+    pub fn hatch_face(&self, face: &RenderFace, attr: Option<&Attributes>) -> Vec<[DVec2; 2]> {
+        let tone = match face_tone(face, attr, self.light.as_ref()) {
+            Some(t) => t,
+            None => return vec![],
+        };
+        let spacing = match self.spacing(tone) {
+            Some(s) => s,
+            None => return vec![],
+        };
+        let mut lines = hatch_loops(face.loops(), self.angle, spacing);
+        if self.cross_hatch.is_some_and(|c| tone < c) {
+            lines.extend(hatch_loops(
+                face.loops(),
+                self.angle + std::f64::consts::FRAC_PI_2,
+                spacing,
+            ));
+        }
+        lines
+    }
+}
+
+/// Clip a set of parallel lines at `angle`, `spacing` apart, to the
+/// interior of `loops` (by the even-odd rule).
+///
+/// The lines are placed at multiples of `spacing` from the origin, so
+/// that the hatching of neighbouring faces lines up.
+pub fn hatch_loops(loops: &[Vec<DVec2>], angle: f64, spacing: f64) -> Vec<[DVec2; 2]> {
+    if spacing <= 0.0 {
+        return vec![];
+    }
+    // u is along the hatch lines, and v across them.
+    let u = vec2(angle.cos(), angle.sin());
+    let v = vec2(-u.y, u.x);
+
+    let (lo, hi) = loops
+        .iter()
+        .flatten()
+        .map(|p| p.dot(&v))
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), c| {
+            (lo.min(c), hi.max(c))
+        });
+    if lo > hi {
+        return vec![];
+    }
+
+    let mut lines = vec![];
+    let mut crossings = vec![];
+    for k in (lo / spacing).ceil() as i64..=(hi / spacing).floor() as i64 {
+        let c = k as f64 * spacing;
+        // skip lines that only touch the outline
+        if c <= lo || c >= hi {
+            continue;
+        }
+        crossings.clear();
+        for l in loops {
+            for i in 0..l.len() {
+                let (a, b) = (l[i], l[(i + 1) % l.len()]);
+                let (da, db) = (a.dot(&v) - c, b.dot(&v) - c);
+                // half-open, so that a line through a vertex crosses
+                // exactly one of its edges
+                if (da > 0.0) != (db > 0.0) {
+                    let p = a + (b - a) * (da / (da - db));
+                    crossings.push(p.dot(&u));
+                }
+            }
+        }
+        crossings.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        for pair in crossings.chunks_exact(2) {
+            if pair[1] - pair[0] > LINE_LENGTH_EPS {
+                lines.push([v * c + u * pair[0], v * c + u * pair[1]]);
+            }
+        }
+    }
+    lines
+}
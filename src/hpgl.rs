@@ -0,0 +1,139 @@
+//! HPGL output, for driving pen plotters directly.
+
+use crate::common::*;
+use crate::page::{Page, Unit};
+use crate::polyline::RenderPolyline;
+use crate::render_paths::{RenderPaths, RenderPoint};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+
+/// Which pen draws each stroke.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PenSelect {
+    /// Draw everything with one pen.
+    Single(u32),
+
+    /// Select the pen by edge type.
+    ByEdgeType(BTreeMap<EdgeType, u32>),
+
+    /// Select the pen by the user layer of the stroke.
+    ByLayer(BTreeMap<String, u32>),
+}
+
+impl PenSelect {
+    /// Pen of `stroke`, or `default` if it has no pen assigned.
+    pub fn pen(&self, paths: &RenderPaths, stroke: &RenderPolyline, default: u32) -> u32 {
+        match self {
+            PenSelect::Single(pen) => *pen,
+            PenSelect::ByEdgeType(pens) => pens.get(&stroke.edge).copied().unwrap_or(default),
+            PenSelect::ByLayer(pens) => layer_pen(paths, stroke.attr, pens, default),
+        }
+    }
+
+    /// Pen of `point`. Points have no edge type, so with `ByEdgeType`
+    /// they are drawn with `default`.
+    pub fn point_pen(&self, paths: &RenderPaths, point: &RenderPoint, default: u32) -> u32 {
+        match self {
+            PenSelect::Single(pen) => *pen,
+            PenSelect::ByEdgeType(_) => default,
+            PenSelect::ByLayer(pens) => layer_pen(paths, point.attr(), pens, default),
+        }
+    }
+
+    /// Group `strokes` by pen, in order of pen number, keeping the
+    /// order of the strokes of each pen.
+    pub(crate) fn group<'a>(
+        &self,
+        paths: &RenderPaths,
+        strokes: &'a [RenderPolyline],
+        default: u32,
+    ) -> BTreeMap<u32, Vec<&'a RenderPolyline>> {
+        let mut pens: BTreeMap<u32, Vec<&RenderPolyline>> = BTreeMap::new();
+        for s in strokes.iter().filter(|s| s.points.len() >= 2) {
+            pens.entry(self.pen(paths, s, default)).or_default().push(s);
+        }
+        pens
+    }
+}
+
+fn layer_pen(
+    paths: &RenderPaths,
+    attr: Option<usize>,
+    pens: &BTreeMap<String, u32>,
+    default: u32,
+) -> u32 {
+    attr.and_then(|i| paths.attributes.get(i))
+        .and_then(|a| a.layer.as_ref())
+        .and_then(|l| pens.get(l))
+        .copied()
+        .unwrap_or(default)
+}
+
+/// Options for `RenderPaths::write_hpgl`.
+#[derive(Debug, Clone)]
+pub struct HpglOptions {
+    /// Page the drawing is laid out on. The plotter origin is the
+    /// bottom left corner of the page.
+    pub page: Page,
+
+    /// Plotter units per millimetre; 40 for most HP plotters.
+    pub units_per_mm: f64,
+
+    pub pens: PenSelect,
+
+    /// Pen of the strokes not assigned one by `pens`.
+    pub default_pen: u32,
+}
+
+impl HpglOptions {
+    pub fn new(page: Page) -> HpglOptions {
+        HpglOptions {
+            page,
+            units_per_mm: 40.0,
+            pens: PenSelect::Single(1),
+            default_pen: 1,
+        }
+    }
+
+    /// Map a point from NDC to plotter units.
+    pub fn to_plotter(&self, p: DVec2) -> (i64, i64) {
+        let q = self.page.to_y_up(p, Unit::Mm) * self.units_per_mm;
+        (q.x.round() as i64, q.y.round() as i64)
+    }
+}
+
+impl RenderPaths {
+    /// Write the lines and polylines as HPGL, one pen at a time.
+    /// Points are plotted as dots, by lowering the pen in place, after
+    /// the strokes of their pen.
+    pub fn write_hpgl<W: Write>(&self, w: &mut W, options: &HpglOptions) -> io::Result<()> {
+        let strokes = self.strokes();
+        let mut pens = options.pens.group(self, &strokes, options.default_pen);
+        let mut points: BTreeMap<u32, Vec<&RenderPoint>> = BTreeMap::new();
+        for p in &self.points {
+            let pen = options.pens.point_pen(self, p, options.default_pen);
+            points.entry(pen).or_default().push(p);
+        }
+        let used: BTreeSet<u32> = pens.keys().chain(points.keys()).copied().collect();
+
+        writeln!(w, "IN;")?;
+        for pen in used {
+            writeln!(w, "SP{};", pen)?;
+            for s in pens.remove(&pen).unwrap_or_default() {
+                let points: Vec<(i64, i64)> =
+                    s.points.iter().map(|p| options.to_plotter(*p)).collect();
+                let (x, y) = points[0];
+                let rest: Vec<String> = points[1..]
+                    .iter()
+                    .map(|(x, y)| format!("{},{}", x, y))
+                    .collect();
+                writeln!(w, "PU{},{};PD{};", x, y, rest.join(","))?;
+            }
+            for p in points.remove(&pen).unwrap_or_default() {
+                let (x, y) = options.to_plotter(p.point());
+                writeln!(w, "PU{},{};PD;PU;", x, y)?;
+            }
+        }
+        writeln!(w, "PU;SP0;")
+    }
+}
@@ -49,11 +49,7 @@ impl RayInt {
 
 impl From<&RayInt> for bool {
     fn from(rhs: &RayInt) -> bool {
-        use RayInt::*;
-        match rhs {
-            Intersection(_, _) => true,
-            _ => false,
-        }
+        matches!(rhs, RayInt::Intersection(_, _))
     }
 }
 
@@ -156,9 +152,11 @@ pub fn implicit_ray_intersect_2d(a0: DVec2, a1: DVec2, b0: DVec2, b1: DVec2) ->
 pub fn triangle_in_triangle_2d(t1: &Tri, t2: &Tri) -> bool {
     // check  that every point in t1 is on or within t2
     t1.p.iter()
-        .all(|p| match point_tri_comparison_test(p.xy(), t2) {
-            PointTriTest::Inside(_) | PointTriTest::On(_) => true,
-            _ => false,
+        .all(|p| {
+            matches!(
+                point_tri_comparison_test(p.xy(), t2),
+                PointTriTest::Inside(_) | PointTriTest::On(_)
+            )
         })
 }
 
@@ -171,7 +169,7 @@ pub fn line_intersect_2d(a0: DVec2, a1: DVec2, b0: DVec2, b1: DVec2) -> RayInt {
     let isect = implicit_ray_intersect_2d(a0.xy(), a1.xy(), b0.xy(), b1.xy());
     match isect {
         RayInt::Intersection(ta, tb) => {
-            if ta >= EPS && ta <= 1.0 - EPS && tb >= EPS && tb <= 1.0 - EPS {
+            if (EPS..=1.0 - EPS).contains(&ta) && (EPS..=1.0 - EPS).contains(&tb) {
                 RayInt::Intersection(ta, tb)
             } else {
                 RayInt::Parallel
@@ -184,7 +182,7 @@ pub fn line_intersect_2d(a0: DVec2, a1: DVec2, b0: DVec2, b1: DVec2) -> RayInt {
 /// Check whether the value is with the open interval (0, 1) using
 /// some epsilon to decide the slack.
 fn inside_line_range(t: f64) -> bool {
-    (t >= EPS) && t <= (1.0 - EPS)
+    (EPS..=1.0 - EPS).contains(&t)
 }
 fn on_line_range(t: f64) -> bool {
     t.abs() < EPS || (1.0 - t).abs() < EPS
@@ -200,7 +198,7 @@ pub enum SplitResult<'a> {
 }
 
 impl<'a> From<&'a Tri> for SplitResult<'a> {
-    fn from(tri: &'a Tri) -> SplitResult {
+    fn from(tri: &'a Tri) -> SplitResult<'a> {
         SplitResult::Original(tri)
     }
 }
@@ -216,7 +214,7 @@ impl<'a> From<Vec<Tri>> for SplitResult<'a> {
 ///
 /// It is assumed within this function that p0-p1 is 'on top' of
 /// `tri`. Degenerate triangles will yield unknown results.
-pub fn split_triangle_by_segment(tri: &Tri, p0: DVec2, p1: DVec2) -> SplitResult {
+pub fn split_triangle_by_segment(tri: &Tri, p0: DVec2, p1: DVec2) -> SplitResult<'_> {
     // println!("let v = [vec4({}, {}, 0.0, 1.0), vec4({},{}, 0.0, 1.0), vec4({},{},0.0,1.0)];
     // let p0 = vec2({}, {});
     // let p1 = vec2({}, {});", tri.p[0].x, tri.p[0].y,
@@ -304,7 +302,7 @@ pub fn split_triangle_by_segment(tri: &Tri, p0: DVec2, p1: DVec2) -> SplitResult
 
 /// Assumes p{0, 1} are of the form (x/w, y/w, z/w, w) and computes
 /// the interpolation along p0 -> p1 such that w fits.
-fn perspective_lerp(t: f64, p0: DVec4, p1: DVec4) -> DVec4 {
+pub(crate) fn perspective_lerp(t: f64, p0: DVec4, p1: DVec4) -> DVec4 {
     let p = (1.0 - t) * p0.xyz() + t * p1.xyz();
     let w = p0.w * p1.w / ((1.0 - t) * p1.w + t * p0.w); // note the reversal; w(0) = p0.w
     vec4(p.x, p.y, p.z, w)
@@ -400,7 +398,7 @@ mod test {
                     assert_approx_eq!(t1, 0.5 * (1.0 - dt));
                     assert_approx_eq!(t2, 0.5);
                 }
-                _ => assert!(false),
+                _ => panic!("expected an intersection"),
             }
         }
     }
@@ -1,11 +1,46 @@
 mod common;
+pub mod convert;
+pub mod dedup;
+pub mod dxf;
+pub mod eps;
+pub mod face;
+pub mod gcode;
+pub mod hatch;
+pub mod hpgl;
 pub mod intersect;
+pub mod mesh;
+pub mod page;
+pub mod pdf;
+pub mod polyline;
 pub mod primitive;
 pub mod render_paths;
 pub mod renderer;
+pub mod scene;
+pub mod shade;
+pub mod stipple;
+pub mod style;
+pub mod tikz;
+pub mod travel;
 
+pub use convert::{FromDVec2, IntoDVec3, ToDMat4};
+pub use dxf::DxfOptions;
+pub use face::RenderFace;
+pub use gcode::{GcodeOptions, Origin, PenLift};
+pub use hatch::HatchOptions;
+pub use hpgl::{HpglOptions, PenSelect};
 pub use intersect::{split_triangle_by_segment, triangle_in_triangle_2d};
-pub use primitive::{EdgeType, Primitive, Tri};
-pub use render_paths::{RenderLine, RenderPaths, SvgOptions};
+pub use mesh::Mesh;
+pub use page::{Fit, Margins, Orientation, Page, PaperSize, Unit};
+pub use pdf::write_pdf_pages;
+pub use polyline::RenderPolyline;
+pub use primitive::{EdgeType, ObjectId, PrimId, Primitive, Tri};
+pub use render_paths::{
+    LayerSplit, Marker, MarkerOptions, RenderLine, RenderPaths, RenderPoint, Stylesheet, SvgOptions,
+};
 //use primitive::ZsortPrim;
 pub use renderer::Renderer;
+pub use scene::{Aabb, Node};
+pub use shade::{Light, Shading};
+pub use stipple::StippleOptions;
+pub use style::{Attributes, Color, LineCap, LineJoin, LineStyle};
+pub use travel::{TravelReport, TravelStats};
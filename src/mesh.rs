@@ -0,0 +1,96 @@
+//! Reusable geometry for instancing.
+
+use crate::common::*;
+use crate::convert::IntoDVec3;
+use crate::primitive::Tri;
+use crate::scene::Aabb;
+
+/// A collection of primitives in model space, that can be added to a
+/// `Renderer` any number of times with `Renderer::add_instance`.
+///
+/// Each primitive gets a mesh-local id, in the same way the
+/// `Renderer::add_*` methods return a `PrimId`. The ids of an
+/// instance's primitives are offset by the base id returned from
+/// `add_instance`.
+#[derive(Debug, Clone, Default)]
+pub struct Mesh {
+    pub(crate) prims: Vec<(Primitive, usize)>,
+    num_ids: usize,
+}
+
+impl Mesh {
+    pub fn new() -> Mesh {
+        Mesh::default()
+    }
+
+    /// Number of ids used by the primitives in the mesh.
+    pub fn num_ids(&self) -> usize {
+        self.num_ids
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prims.is_empty()
+    }
+
+    /// Return the bounding box of the mesh, or `None` if it is empty.
+    pub fn bounds(&self) -> Option<Aabb> {
+        let points: Vec<DVec3> = self
+            .prims
+            .iter()
+            .flat_map(|(prim, _)| match prim {
+                Primitive::Point { point } => vec![point.xyz()],
+                Primitive::Line { points } => points.iter().map(|p| p.xyz()).collect(),
+                Primitive::Triangle { tri } => tri.p.iter().map(|p| p.xyz()).collect(),
+            })
+            .collect();
+        Aabb::from_points(&points)
+    }
+
+    fn next_id(&mut self) -> usize {
+        self.num_ids += 1;
+        self.num_ids - 1
+    }
+
+    /// Add a primitive to the mesh, returning its local id.
+    pub fn add_prim(&mut self, p: Primitive) -> usize {
+        let id = self.next_id();
+        self.prims.push((p, id));
+        id
+    }
+
+    pub fn add_point(&mut self, p: impl IntoDVec3) -> usize {
+        self.add_prim(Primitive::Point {
+            point: p.into_dvec3().push(1.0),
+        })
+    }
+
+    pub fn add_line(&mut self, p0: impl IntoDVec3, p1: impl IntoDVec3) -> usize {
+        self.add_prim(Primitive::Line {
+            points: [p0.into_dvec3().push(1.0), p1.into_dvec3().push(1.0)],
+        })
+    }
+
+    /// Add a triangle to the mesh, with all visible edges.
+    pub fn add_triangle<V: IntoDVec3>(&mut self, p0: V, p1: V, p2: V) -> usize {
+        self.add_prim(Primitive::Triangle {
+            tri: Tri {
+                p: [
+                    p0.into_dvec3().push(1.0),
+                    p1.into_dvec3().push(1.0),
+                    p2.into_dvec3().push(1.0),
+                ],
+                e: [EdgeType::Visible; 3],
+            },
+        })
+    }
+
+    /// Add a polygon to the mesh. See `Renderer::add_polygon`.
+    pub fn add_polygon<V: IntoDVec3 + Copy>(&mut self, p: &[V]) -> usize {
+        let points: Vec<_> = p.iter().map(|v| v.into_dvec3().push(1.0)).collect();
+        let id = self.next_id();
+        for tri in Tri::fan(&points) {
+            self.prims.push((Primitive::Triangle { tri }, id));
+        }
+        id
+    }
+}
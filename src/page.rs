@@ -0,0 +1,176 @@
+//! Page layout of the output: physical size, units and margins, and
+//! how the rendered NDC square is fitted onto the page.
+
+use crate::common::*;
+use std::fmt;
+
+/// Unit of the page dimensions, and of all output coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Px,
+    Mm,
+    In,
+}
+
+impl Unit {
+    /// Number of units per inch. Pixels are CSS pixels, at 96 per
+    /// inch.
+    pub fn per_inch(&self) -> f64 {
+        match self {
+            Unit::Px => 96.0,
+            Unit::Mm => 25.4,
+            Unit::In => 1.0,
+        }
+    }
+
+    /// Convert `x` from this unit into `to`.
+    pub fn convert(&self, x: f64, to: Unit) -> f64 {
+        x * to.per_inch() / self.per_inch()
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Unit::Px => "px",
+            Unit::Mm => "mm",
+            Unit::In => "in",
+        })
+    }
+}
+
+/// Standard paper sizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaperSize {
+    A3,
+    A4,
+    Letter,
+}
+
+impl PaperSize {
+    /// Portrait width and height, in millimetres.
+    pub fn size_mm(&self) -> (f64, f64) {
+        match self {
+            PaperSize::A3 => (297.0, 420.0),
+            PaperSize::A4 => (210.0, 297.0),
+            PaperSize::Letter => (215.9, 279.4),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+/// Blank space around the drawing, in page units.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Margins {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+impl Margins {
+    /// The same margin on all four sides.
+    pub fn uniform(m: f64) -> Margins {
+        Margins {
+            top: m,
+            right: m,
+            bottom: m,
+            left: m,
+        }
+    }
+}
+
+/// How the NDC square [-1, 1] x [-1, 1] is mapped onto the drawing
+/// area of the page.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fit {
+    /// Fill the whole drawing area, stretching the image if the
+    /// aspect ratios differ.
+    Stretch,
+
+    /// Fit the image, of `aspect` (width / height, usually that of
+    /// the camera's projection) into the drawing area, centred and
+    /// without distortion. An `aspect` that is not positive and
+    /// finite is treated as `Stretch`.
+    Contain { aspect: f64 },
+}
+
+/// A page to lay the output out on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Page {
+    pub width: f64,
+    pub height: f64,
+    pub unit: Unit,
+    pub margins: Margins,
+    pub fit: Fit,
+}
+
+impl Page {
+    pub fn new(width: f64, height: f64, unit: Unit) -> Page {
+        Page {
+            width,
+            height,
+            unit,
+            margins: Margins::default(),
+            fit: Fit::Stretch,
+        }
+    }
+
+    /// A page of a standard paper size, in millimetres.
+    pub fn paper(size: PaperSize, orientation: Orientation) -> Page {
+        let (w, h) = size.size_mm();
+        match orientation {
+            Orientation::Portrait => Page::new(w, h, Unit::Mm),
+            Orientation::Landscape => Page::new(h, w, Unit::Mm),
+        }
+    }
+
+    /// Origin (top left) and size of the region the image is drawn
+    /// into, in page units.
+    pub fn drawing_area(&self) -> (DVec2, DVec2) {
+        let m = &self.margins;
+        let origin = vec2(m.left, m.top);
+        let size = vec2(
+            (self.width - m.left - m.right).max(0.0),
+            (self.height - m.top - m.bottom).max(0.0),
+        );
+        match self.fit {
+            Fit::Contain { aspect } if aspect > 0.0 && aspect.is_finite() => {
+                let fitted = if size.x > size.y * aspect {
+                    vec2(size.y * aspect, size.y)
+                } else {
+                    vec2(size.x, size.x / aspect)
+                };
+                (origin + (size - fitted) * 0.5, fitted)
+            }
+            _ => (origin, size),
+        }
+    }
+
+    /// Map a point from NDC to page coordinates, with y pointing down.
+    pub fn to_page(&self, p: DVec2) -> DVec2 {
+        let (origin, size) = self.drawing_area();
+        origin + vec2(size.x * (1.0 + p.x), size.y * (1.0 - p.y)) * 0.5
+    }
+
+    /// Map a point from NDC to `unit`s from the bottom left corner of
+    /// the page, with y pointing up, as used by plotters and most
+    /// formats other than SVG.
+    pub fn to_y_up(&self, p: DVec2, unit: Unit) -> DVec2 {
+        let q = self.to_page(p);
+        vec2(
+            self.unit.convert(q.x, unit),
+            self.unit.convert(self.height - q.y, unit),
+        )
+    }
+
+    /// The `viewBox` of an SVG document of the page.
+    pub fn view_box(&self) -> String {
+        format!("0 0 {} {}", self.width, self.height)
+    }
+}
@@ -0,0 +1,221 @@
+//! PDF output, drawn with the same styles and page as the SVG output.
+
+use crate::common::*;
+use crate::page::Unit;
+use crate::render_paths::{Marker, RenderPaths, SvgOptions};
+use crate::style::{Color, LineCap, LineJoin, LineStyle};
+use std::io::{self, Write};
+
+/// Points per inch, the PDF (and PostScript) user space unit.
+pub(crate) const PT_PER_INCH: f64 = 72.0;
+
+/// Format a number for a content stream, without trailing zeros.
+pub(crate) fn num(x: f64) -> String {
+    let s = format!("{:.3}", x);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" {
+        "0".to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+pub(crate) fn rgb(c: Color) -> String {
+    let f = |x: u8| num(x as f64 / 255.0);
+    format!("{} {} {}", f(c.r), f(c.g), f(c.b))
+}
+
+/// Line cap and join codes of the style, shared by PDF and
+/// PostScript.
+pub(crate) fn cap_join(style: &LineStyle) -> (u8, u8) {
+    let cap = match style.cap {
+        LineCap::Butt => 0,
+        LineCap::Round => 1,
+        LineCap::Square => 2,
+    };
+    let join = match style.join {
+        LineJoin::Miter => 0,
+        LineJoin::Round => 1,
+        LineJoin::Bevel => 2,
+    };
+    (cap, join)
+}
+
+/// Graphics state operators setting the stroke style. Lengths are
+/// converted from page units by `scale`.
+fn stroke_state(style: &LineStyle, scale: f64) -> String {
+    let dash: Vec<String> = style.dash.iter().map(|d| num(d * scale)).collect();
+    let (cap, join) = cap_join(style);
+    format!(
+        "{} RG {} w [{}] 0 d {} J {} j\n",
+        rgb(style.stroke),
+        num(style.width * scale),
+        dash.join(" "),
+        cap,
+        join
+    )
+}
+
+/// Path operators of a marker of `shape` and radius `r` at `c`,
+/// filled or stroked as for the SVG markers. Circles are drawn with
+/// four cubic Bézier arcs.
+fn marker_path(shape: Marker, c: DVec2, r: f64) -> String {
+    let p = |x: f64, y: f64| format!("{} {}", num(c.x + x), num(c.y + y));
+    match shape {
+        Marker::Circle => {
+            // control point offset of a quarter circle
+            let k = r * 0.552_284_75;
+            format!(
+                "{} m\n{} {} {} c\n{} {} {} c\n{} {} {} c\n{} {} {} c\nf\n",
+                p(r, 0.0),
+                p(r, k),
+                p(k, r),
+                p(0.0, r),
+                p(-k, r),
+                p(-r, k),
+                p(-r, 0.0),
+                p(-r, -k),
+                p(-k, -r),
+                p(0.0, -r),
+                p(k, -r),
+                p(r, -k),
+                p(r, 0.0)
+            )
+        }
+        Marker::Square => format!(
+            "{} {} {} re\nf\n",
+            p(-r, -r),
+            num(2.0 * r),
+            num(2.0 * r)
+        ),
+        Marker::Cross => format!(
+            "{} m\n{} l\n{} m\n{} l\nS\n",
+            p(-r, -r),
+            p(r, r),
+            p(-r, r),
+            p(r, -r)
+        ),
+    }
+}
+
+impl RenderPaths {
+    /// Write the faces, lines and points as a one-page PDF document.
+    pub fn write_pdf<W: Write>(&self, w: &mut W, options: &SvgOptions) -> io::Result<()> {
+        write_pdf_pages(w, &[self], options)
+    }
+
+    /// PDF content stream of the page.
+    fn pdf_content(&self, options: &SvgOptions) -> String {
+        let page = &options.page;
+        let scale = page.unit.convert(1.0, Unit::In) * PT_PER_INCH;
+        let pt = |p: &DVec2| {
+            let q = page.to_y_up(*p, Unit::In) * PT_PER_INCH;
+            format!("{} {}", num(q.x), num(q.y))
+        };
+
+        let mut out = String::new();
+        if options.faces {
+            for face in &self.faces {
+                let fill = self.face_color(face, options).unwrap_or(options.face_fill);
+                out += &format!("{} rg\n", rgb(fill));
+                for l in face.loops.iter().filter(|l| !l.is_empty()) {
+                    out += &format!("{} m\n", pt(&l[0]));
+                    for p in &l[1..] {
+                        out += &format!("{} l\n", pt(p));
+                    }
+                    out += "h\n";
+                }
+                // Even-odd, as for `RenderFace::contains`.
+                out += "f*\n";
+            }
+        }
+
+        let mut state = String::new();
+        for s in self.strokes_in_paint_order(options) {
+            if s.points.len() < 2 {
+                continue;
+            }
+            let next = stroke_state(&self.stroke_style(&s, options), scale);
+            if next != state {
+                out += &next;
+                state = next;
+            }
+            out += &format!("{} m\n", pt(&s.points[0]));
+            for p in &s.points[1..] {
+                out += &format!("{} l\n", pt(p));
+            }
+            out += "S\n";
+        }
+
+        if !self.points.is_empty() {
+            let markers = &options.markers;
+            out += &match markers.shape {
+                Marker::Cross => format!(
+                    "{} RG {} w [] 0 d 0 J 0 j\n",
+                    rgb(markers.color),
+                    num(markers.stroke_width * scale)
+                ),
+                _ => format!("{} rg\n", rgb(markers.color)),
+            };
+            for point in &self.points {
+                let c = page.to_y_up(point.point(), Unit::In) * PT_PER_INCH;
+                out += &marker_path(markers.shape, c, markers.point_size(point) * scale);
+            }
+        }
+        out
+    }
+}
+
+/// Write each of `pages` as one page of a PDF document, all with the
+/// page size and styles of `options`.
+pub fn write_pdf_pages<W: Write>(
+    w: &mut W,
+    pages: &[&RenderPaths],
+    options: &SvgOptions,
+) -> io::Result<()> {
+    let page = &options.page;
+    let width = page.unit.convert(page.width, Unit::In) * PT_PER_INCH;
+    let height = page.unit.convert(page.height, Unit::In) * PT_PER_INCH;
+
+    // Objects 1 and 2 are the catalog and the page tree, followed by
+    // the page and content stream of each page.
+    let kids: Vec<String> = (0..pages.len()).map(|i| format!("{} 0 R", 3 + 2 * i)).collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()),
+    ];
+    for (i, paths) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Contents {} 0 R >>",
+            num(width),
+            num(height),
+            4 + 2 * i
+        ));
+        let content = paths.pdf_content(options);
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ));
+    }
+
+    let mut out: Vec<u8> = b"%PDF-1.4\n".to_vec();
+    let mut offsets = vec![];
+    for (i, obj) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        write!(out, "{} 0 obj\n{}\nendobj\n", i + 1, obj)?;
+    }
+    let xref = out.len();
+    writeln!(out, "xref\n0 {}", objects.len() + 1)?;
+    writeln!(out, "0000000000 65535 f ")?;
+    for offset in offsets {
+        writeln!(out, "{:010} 00000 n ", offset)?;
+    }
+    write!(
+        out,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    )?;
+    w.write_all(&out)
+}
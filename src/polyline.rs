@@ -0,0 +1,244 @@
+//! Polylines chained together from connected `RenderLine`s.
+//!
+//! Splitting cuts the edges of the source triangles into many short
+//! pieces. Chaining them back into polylines keeps the pen of a
+//! plotter down across the pieces.
+
+use crate::common::*;
+use crate::primitive::{ObjectId, PrimId};
+use crate::render_paths::RenderLine;
+use std::collections::{BTreeMap, HashMap};
+
+/// A connected chain of lines of a single edge type and style. If
+/// closed, the first and last points are the same.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderPolyline {
+    pub(crate) points: Vec<DVec2>,
+    pub(crate) depth: Vec<f64>,
+    pub(crate) w: Vec<f64>,
+    pub(crate) edge: EdgeType,
+    pub(crate) attr: Option<usize>,
+    pub(crate) id: Option<PrimId>,
+    pub(crate) object: Option<ObjectId>,
+}
+
+impl RenderPolyline {
+    /// The 2d (NDC) points of the polyline.
+    pub fn points(&self) -> &[DVec2] {
+        &self.points
+    }
+
+    /// NDC depth of each point.
+    pub fn depth(&self) -> &[f64] {
+        &self.depth
+    }
+
+    /// Clip-space w of each point.
+    pub fn w(&self) -> &[f64] {
+        &self.w
+    }
+
+    /// The points of the polyline, of the form (x/w, y/w, z/w, w).
+    pub fn ndc_points(&self) -> Vec<DVec4> {
+        (0..self.points.len())
+            .map(|i| vec4(self.points[i].x, self.points[i].y, self.depth[i], self.w[i]))
+            .collect()
+    }
+
+    pub fn edge(&self) -> EdgeType {
+        self.edge
+    }
+
+    /// Index into `RenderPaths::attributes` of this polyline's
+    /// attributes.
+    pub fn attr(&self) -> Option<usize> {
+        self.attr
+    }
+
+    /// Id of the primitive this polyline was generated from, if all of
+    /// its lines came from the same one.
+    pub fn id(&self) -> Option<PrimId> {
+        self.id
+    }
+
+    /// Id of the object this polyline belongs to.
+    pub fn object(&self) -> Option<ObjectId> {
+        self.object
+    }
+
+    /// Return true iff the polyline ends where it starts.
+    pub fn is_closed(&self) -> bool {
+        self.points.len() > 2 && self.points.first() == self.points.last()
+    }
+
+    /// 2d length of the polyline.
+    pub fn length(&self) -> f64 {
+        self.points.windows(2).map(|w| (w[1] - w[0]).norm()).sum()
+    }
+
+    /// Reverse the direction of the polyline.
+    pub fn reverse(&mut self) {
+        self.points.reverse();
+        self.depth.reverse();
+        self.w.reverse();
+    }
+}
+
+impl From<&RenderLine> for RenderPolyline {
+    fn from(line: &RenderLine) -> RenderPolyline {
+        RenderPolyline {
+            points: line.points().to_vec(),
+            depth: line.depth().to_vec(),
+            w: line.w().to_vec(),
+            edge: line.edge(),
+            attr: line.attr(),
+            id: line.id(),
+            object: line.object(),
+        }
+    }
+}
+
+/// Lines are only merged with others of the same edge type,
+/// attributes and object.
+type LineKey = (EdgeType, Option<usize>, Option<ObjectId>);
+
+/// Vertex indices along a chain, and the segments it is made of.
+type Chain = (Vec<usize>, Vec<usize>);
+
+/// Chain `lines` into polylines. Lines are joined where their end
+/// points are within `tolerance`, if they have the same edge type,
+/// attributes and object. Runs of collinear points (within
+/// `tolerance`) are merged. The depth and w of the points are kept;
+/// where end points are welded, those of the first one are used.
+pub fn merge_lines(lines: &[RenderLine], tolerance: f64) -> Vec<RenderPolyline> {
+    let mut groups: BTreeMap<LineKey, Vec<&RenderLine>> = BTreeMap::new();
+    for line in lines {
+        groups
+            .entry((line.edge(), line.attr(), line.object()))
+            .or_default()
+            .push(line);
+    }
+
+    let mut polylines = vec![];
+    for ((edge, attr, object), lines) in groups {
+        let (verts, chains) = chain_segments(
+            &lines.iter().map(|l| l.ndc_points()).collect::<Vec<_>>(),
+            tolerance,
+        );
+        for (chain, segs) in chains {
+            let mut ids = segs.iter().map(|s| lines[*s].id());
+            let first = ids.next().flatten();
+            let id = if ids.all(|id| id == first) { first } else { None };
+            let points = simplify(chain.iter().map(|v| verts[*v]).collect(), tolerance);
+            polylines.push(RenderPolyline {
+                points: points.iter().map(|p| p.xy()).collect(),
+                depth: points.iter().map(|p| p.z).collect(),
+                w: points.iter().map(|p| p.w).collect(),
+                edge,
+                attr,
+                id,
+                object,
+            });
+        }
+    }
+    polylines
+}
+
+/// Weld the end points of `segments` (of the form (x/w, y/w, z/w, w))
+/// within `tolerance` in 2d, and walk the resulting graph into chains
+/// of vertex indices. Also returns the segments making up each chain.
+fn chain_segments(segments: &[[DVec4; 2]], tolerance: f64) -> (Vec<DVec4>, Vec<Chain>) {
+    // Hash the vertices into cells of size `tolerance`, so that only
+    // the neighbouring cells need to be searched when welding.
+    let cell = tolerance.max(f64::EPSILON);
+    let key = |p: DVec2| ((p.x / cell).floor() as i64, (p.y / cell).floor() as i64);
+    let mut grid: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    let mut verts: Vec<DVec4> = vec![];
+    let mut index = |p: DVec4| {
+        let (kx, ky) = key(p.xy());
+        for x in kx - 1..=kx + 1 {
+            for y in ky - 1..=ky + 1 {
+                for &i in grid.get(&(x, y)).into_iter().flatten() {
+                    if (verts[i].xy() - p.xy()).norm() <= tolerance {
+                        return i;
+                    }
+                }
+            }
+        }
+        verts.push(p);
+        grid.entry((kx, ky)).or_default().push(verts.len() - 1);
+        verts.len() - 1
+    };
+
+    let mut edges: Vec<[usize; 2]> = vec![];
+    let mut seg_of_edge = vec![];
+    for (s, seg) in segments.iter().enumerate() {
+        let e = [index(seg[0]), index(seg[1])];
+        if e[0] != e[1] {
+            edges.push(e);
+            seg_of_edge.push(s);
+        }
+    }
+    let mut adjacent: Vec<Vec<usize>> = vec![vec![]; verts.len()];
+    for (i, e) in edges.iter().enumerate() {
+        adjacent[e[0]].push(i);
+        adjacent[e[1]].push(i);
+    }
+
+    // Start walking from the ends and junctions, so that open chains
+    // are not broken in the middle, and then pick up the loops.
+    let starts: Vec<usize> = (0..verts.len())
+        .filter(|v| adjacent[*v].len() != 2)
+        .chain(0..verts.len())
+        .collect();
+    let mut used = vec![false; edges.len()];
+    let mut chains = vec![];
+    for start in starts {
+        while let Some(&first) = adjacent[start].iter().find(|e| !used[**e]) {
+            let mut chain = vec![start];
+            let mut segs = vec![];
+            let (mut cur, mut e) = (start, first);
+            loop {
+                used[e] = true;
+                segs.push(seg_of_edge[e]);
+                cur = if edges[e][0] == cur {
+                    edges[e][1]
+                } else {
+                    edges[e][0]
+                };
+                chain.push(cur);
+                match adjacent[cur].iter().find(|e| !used[**e]) {
+                    Some(&next) => e = next,
+                    None => break,
+                }
+            }
+            chains.push((chain, segs));
+        }
+    }
+    (verts, chains)
+}
+
+/// Remove the points of a polyline that lie (within `tolerance`) on
+/// the straight line between their neighbours in 2d.
+fn simplify(points: Vec<DVec4>, tolerance: f64) -> Vec<DVec4> {
+    let mut out: Vec<DVec4> = Vec::with_capacity(points.len());
+    for q in points {
+        let p = q.xy();
+        if out.len() >= 2 {
+            let (a, b) = (out[out.len() - 2].xy(), out[out.len() - 1].xy());
+            let (ab, ac) = (b - a, p - a);
+            let len = ac.norm();
+            let off = if len > 0.0 {
+                (ab.x * ac.y - ab.y * ac.x).abs() / len
+            } else {
+                f64::INFINITY
+            };
+            // only drop b if the line keeps going the same way
+            if off <= tolerance && ab.dot(&(p - b)) > 0.0 {
+                out.pop();
+            }
+        }
+        out.push(q);
+    }
+    out
+}
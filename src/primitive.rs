@@ -1,16 +1,32 @@
 use crate::common::*;
 use std::collections::HashSet;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Identifier of a primitive added to the `Renderer`.
+pub type PrimId = usize;
+
+/// User-supplied identifier of the object a primitive belongs to.
+pub type ObjectId = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum EdgeType {
     Visible,
     Invisible,
     Hidden, // behind another triangle
     Split,  // generated by an internal split
     Culled,
+    Hatch, // hatching of a visible face
 }
 
 impl EdgeType {
+    pub const ALL: [EdgeType; 6] = [
+        EdgeType::Visible,
+        EdgeType::Invisible,
+        EdgeType::Hidden,
+        EdgeType::Split,
+        EdgeType::Culled,
+        EdgeType::Hatch,
+    ];
+
     pub fn class_name(&self) -> &'static str {
 	use EdgeType::*;
 	match self {
@@ -19,6 +35,7 @@ impl EdgeType {
 	    Hidden => "hidden",
 	    Split => "split",
 	    Culled => "culled",
+	    Hatch => "hatch",
 	}
     }
 }
@@ -37,6 +54,29 @@ pub struct Tri {
 }
 
 impl Tri {
+    /// Split a convex polygon into a triangle fan, with all outside
+    /// edges visible and the inner edges marked as Invisible.
+    pub fn fan(p: &[DVec4]) -> Vec<Tri> {
+        (0..p.len().saturating_sub(2))
+            .map(|i| {
+                let e0 = if i == 0 {
+                    EdgeType::Visible
+                } else {
+                    EdgeType::Invisible
+                };
+                let e2 = if i == p.len() - 3 {
+                    EdgeType::Visible
+                } else {
+                    EdgeType::Invisible
+                };
+                Tri {
+                    p: [p[0], p[i + 1], p[i + 2]],
+                    e: [e0, EdgeType::Visible, e2],
+                }
+            })
+            .collect()
+    }
+
     /// Hide all edges of the triangle, consuming itself.
     pub fn hide(&mut self) {
         self.e[0] = EdgeType::Hidden;
@@ -115,9 +155,8 @@ impl Primitive {
     }
 
     pub fn hide(&mut self) {
-        match self {
-            Primitive::Triangle { ref mut tri } => tri.hide(),
-            _ => {}
+        if let Primitive::Triangle { ref mut tri } = self {
+            tri.hide()
         }
     }
 }
@@ -129,6 +168,19 @@ pub struct ZsortPrim {
     pub p: Primitive,
     z: f64,
     pub presplit: HashSet<(usize, usize)>,
+
+    /// Index of the `Attributes` of the source primitive, if any.
+    pub attr: Option<usize>,
+
+    /// Id of the source primitive.
+    pub id: Option<PrimId>,
+
+    /// Id of the object the source primitive belongs to.
+    pub object: Option<ObjectId>,
+
+    /// World-space unit normal of the source triangle, facing the
+    /// eye.
+    pub normal: Option<DVec3>,
 }
 
 impl From<Primitive> for ZsortPrim {
@@ -138,6 +190,10 @@ impl From<Primitive> for ZsortPrim {
             p,
             z,
             presplit: HashSet::new(),
+            attr: None,
+            id: None,
+            object: None,
+            normal: None,
         }
     }
 }
@@ -149,6 +205,22 @@ impl ZsortPrim {
             p,
             z,
             presplit: hs.clone(),
+            attr: None,
+            id: None,
+            object: None,
+            normal: None,
+        }
+    }
+
+    /// Create a new `ZsortPrim` from a fragment of this one, keeping
+    /// track of the source primitive's ids and attributes.
+    pub fn fragment(&self, p: Primitive, hs: &HashSet<(usize, usize)>) -> ZsortPrim {
+        ZsortPrim {
+            attr: self.attr,
+            id: self.id,
+            object: self.object,
+            normal: self.normal,
+            ..ZsortPrim::new(p, hs)
         }
     }
 
@@ -161,12 +233,12 @@ impl Eq for ZsortPrim {}
 
 impl std::cmp::PartialOrd for ZsortPrim {
     fn partial_cmp(&self, rhs: &Self) -> Option<std::cmp::Ordering> {
-        self.z.partial_cmp(&rhs.z)
+        Some(self.cmp(rhs))
     }
 }
 
 impl std::cmp::Ord for ZsortPrim {
     fn cmp(&self, rhs: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(rhs).unwrap()
+        self.z.partial_cmp(&rhs.z).unwrap()
     }
 }
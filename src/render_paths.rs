@@ -5,50 +5,541 @@
 //! during the rendering process.
 
 use crate::common::*;
-use crate::primitive::{Tri, ZsortPrim};
-use std::collections::HashMap;
-use svg::Document;
+use crate::convert::FromDVec2;
+use crate::dedup;
+use crate::face::RenderFace;
+use crate::hatch::HatchOptions;
+use crate::page::{Page, Unit};
+use crate::polyline::{self, RenderPolyline};
+use crate::primitive::{ObjectId, PrimId, Tri, ZsortPrim};
+use crate::shade::Shading;
+use crate::stipple::StippleOptions;
+use crate::style::{css_rule, Attributes, Color, LineStyle};
+use crate::travel::{self, TravelReport, TravelStats};
+use std::collections::{BTreeMap, HashMap};
+use svg::{Document, Node};
 use svg::node::element::{self, Group, Style};
 
 /// Output line from the `Renderer`.
 #[derive(Debug, Clone, Copy)]
 pub struct RenderLine {
     points: [DVec2; 2],
+    depth: [f64; 2],
+    w: [f64; 2],
     edge: EdgeType,
+    attr: Option<usize>,
+    id: Option<PrimId>,
+    object: Option<ObjectId>,
 }
 
 impl RenderLine {
     pub fn new(p0: DVec2, p1: DVec2, e: EdgeType) -> RenderLine {
         RenderLine {
             points: [p0, p1],
+            depth: [0.0; 2],
+            w: [1.0; 2],
             edge: e,
+            attr: None,
+            id: None,
+            object: None,
+        }
+    }
+
+    /// Create a line from two points of the form (x/w, y/w, z/w, w),
+    /// keeping the depth information.
+    pub fn from_ndc(p0: DVec4, p1: DVec4, e: EdgeType) -> RenderLine {
+        RenderLine {
+            depth: [p0.z, p1.z],
+            w: [p0.w, p1.w],
+            ..RenderLine::new(p0.xy(), p1.xy(), e)
+        }
+    }
+
+    /// Return this line with its attributes set to index `attr` of
+    /// `RenderPaths::attributes`.
+    pub fn with_attr(self, attr: Option<usize>) -> RenderLine {
+        RenderLine { attr, ..self }
+    }
+
+    /// Return this line with its end points moved to `p0` and `p1`,
+    /// of the form (x/w, y/w, z/w, w).
+    pub(crate) fn with_points(self, p0: DVec4, p1: DVec4) -> RenderLine {
+        RenderLine {
+            points: [p0.xy(), p1.xy()],
+            depth: [p0.z, p1.z],
+            w: [p0.w, p1.w],
+            ..self
+        }
+    }
+
+    /// Return this line with the ids and attributes of the primitive
+    /// it was generated from.
+    fn tagged(self, prim: &ZsortPrim) -> RenderLine {
+        RenderLine {
+            attr: prim.attr,
+            id: prim.id,
+            object: prim.object,
+            ..self
+        }
+    }
+
+    /// The 2d (NDC) end points of the line.
+    pub fn points(&self) -> [DVec2; 2] {
+        self.points
+    }
+
+    /// The 2d end points of the line, converted to another point type.
+    pub fn points_as<T: FromDVec2>(&self) -> [T; 2] {
+        [T::from_dvec2(self.points[0]), T::from_dvec2(self.points[1])]
+    }
+
+    /// The NDC depth (z/w) at each end point.
+    pub fn depth(&self) -> [f64; 2] {
+        self.depth
+    }
+
+    /// The clip-space w at each end point.
+    pub fn w(&self) -> [f64; 2] {
+        self.w
+    }
+
+    /// The end points in the form (x/w, y/w, z/w, w).
+    pub fn ndc_points(&self) -> [DVec4; 2] {
+        [
+            vec4(self.points[0].x, self.points[0].y, self.depth[0], self.w[0]),
+            vec4(self.points[1].x, self.points[1].y, self.depth[1], self.w[1]),
+        ]
+    }
+
+    pub fn edge(&self) -> EdgeType {
+        self.edge
+    }
+
+    /// Index into `RenderPaths::attributes` of this line's attributes.
+    pub fn attr(&self) -> Option<usize> {
+        self.attr
+    }
+
+    /// 2d length of the line.
+    pub fn length(&self) -> f64 {
+        (self.points[1] - self.points[0]).norm()
+    }
+
+    /// Id of the primitive this line was generated from, as returned
+    /// by the `Renderer::add_*` methods.
+    pub fn id(&self) -> Option<PrimId> {
+        self.id
+    }
+
+    /// Id of the object this line belongs to, as set by
+    /// `Renderer::set_object`.
+    pub fn object(&self) -> Option<ObjectId> {
+        self.object
+    }
+}
+
+/// Output point from the `Renderer`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderPoint {
+    point: DVec2,
+    depth: f64,
+    w: f64,
+    attr: Option<usize>,
+    id: Option<PrimId>,
+    object: Option<ObjectId>,
+}
+
+impl RenderPoint {
+    pub fn new(p: DVec2) -> RenderPoint {
+        RenderPoint {
+            point: p,
+            depth: 0.0,
+            w: 1.0,
+            attr: None,
+            id: None,
+            object: None,
+        }
+    }
+
+    /// Create a point of the form (x/w, y/w, z/w, w), keeping the
+    /// depth information.
+    pub fn from_ndc(p: DVec4) -> RenderPoint {
+        RenderPoint {
+            depth: p.z,
+            w: p.w,
+            ..RenderPoint::new(p.xy())
+        }
+    }
+
+    /// Return this point with the ids and attributes of the primitive
+    /// it was generated from.
+    fn tagged(self, prim: &ZsortPrim) -> RenderPoint {
+        RenderPoint {
+            attr: prim.attr,
+            id: prim.id,
+            object: prim.object,
+            ..self
+        }
+    }
+
+    /// The 2d (NDC) position of the point.
+    pub fn point(&self) -> DVec2 {
+        self.point
+    }
+
+    /// The 2d position of the point, converted to another point type.
+    pub fn point_as<T: FromDVec2>(&self) -> T {
+        T::from_dvec2(self.point)
+    }
+
+    /// The NDC depth (z/w) of the point.
+    pub fn depth(&self) -> f64 {
+        self.depth
+    }
+
+    /// The clip-space w of the point.
+    pub fn w(&self) -> f64 {
+        self.w
+    }
+
+    /// Index into `RenderPaths::attributes` of this point's
+    /// attributes.
+    pub fn attr(&self) -> Option<usize> {
+        self.attr
+    }
+
+    /// Id of the primitive this point was generated from.
+    pub fn id(&self) -> Option<PrimId> {
+        self.id
+    }
+
+    /// Id of the object this point belongs to.
+    pub fn object(&self) -> Option<ObjectId> {
+        self.object
+    }
+}
+
+/// Shape of the marker drawn for each point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Marker {
+    Circle,
+    Cross,
+    Square,
+}
+
+impl Marker {
+    pub fn class_name(&self) -> &'static str {
+        match self {
+            Marker::Circle => "circle",
+            Marker::Cross => "cross",
+            Marker::Square => "square",
+        }
+    }
+}
+
+/// How points are drawn in the SVG output.
+#[derive(Debug, Clone)]
+pub struct MarkerOptions {
+    pub shape: Marker,
+
+    /// Radius (or half width) of the markers, in output units.
+    pub size: f64,
+
+    /// If set, the size of the markers at the far plane (NDC z = 1),
+    /// relative to the near plane (z = -1). Sizes in between are
+    /// interpolated linearly.
+    pub depth_scale: Option<f64>,
+
+    /// CSS class of the markers. Each marker also gets the class of
+    /// its shape.
+    pub class: String,
+
+    /// Fill colour of circles and squares, and stroke colour of
+    /// crosses.
+    pub color: Color,
+
+    /// Stroke width of crosses, in output units.
+    pub stroke_width: f64,
+}
+
+impl MarkerOptions {
+    /// Size of the marker of `point`.
+    pub fn point_size(&self, point: &RenderPoint) -> f64 {
+        match self.depth_scale {
+            Some(far) => {
+                let t = (point.depth.clamp(-1.0, 1.0) + 1.0) * 0.5;
+                self.size * (1.0 + (far - 1.0) * t)
+            }
+            None => self.size,
+        }
+    }
+}
+
+impl Default for MarkerOptions {
+    fn default() -> MarkerOptions {
+        MarkerOptions {
+            shape: Marker::Circle,
+            size: 1.5,
+            depth_scale: None,
+            class: "point".to_string(),
+            color: Color::rgb(0x44, 0x44, 0x44),
+            stroke_width: 0.5,
         }
     }
 }
 
 pub struct SvgOptions {
-    pub width: f64,
-    pub height: f64,
+    /// Size and layout of the page. All styles are in the units of
+    /// the page.
+    pub page: Page,
+
+    /// If true, the lines are grouped by user layer, and then by edge
+    /// type.
     pub by_layer: bool,
+
+    /// Paint order of the edge type groups with `by_layer`, from
+    /// bottom to top. Edge types not listed are painted last, in the
+    /// order of `EdgeType::ALL`.
+    pub edge_order: Vec<EdgeType>,
+
+    /// Names of the edge type groups, written as their `data-name`.
+    pub edge_names: BTreeMap<EdgeType, String>,
+
+    /// Paint order of the user layers with `by_layer`, from bottom to
+    /// top. Layers not listed are painted last, in the order they
+    /// were first set on the `Renderer`. Lines without a layer are
+    /// always painted first.
+    pub layer_order: Vec<String>,
+
+    /// If set, the lines are written as Inkscape layers (`<g
+    /// inkscape:groupmode="layer">`), split as given, instead of
+    /// being grouped by `by_layer` or `by_object`. The faces and
+    /// points get layers of their own.
+    pub inkscape_layers: Option<LayerSplit>,
+
+    /// If true, the lines of each object are wrapped in a
+    /// `<g id="object-N">` element.
+    pub by_object: bool,
+
+    /// If true, the visible faces are drawn as filled polygons
+    /// underneath all of the lines.
+    pub faces: bool,
+
+    /// If set, the faces are filled with their attribute (or base)
+    /// colour, shaded by the light.
+    pub shading: Option<Shading>,
+
+    /// Markers drawn for the points.
+    pub markers: MarkerOptions,
+
+    /// Style of the lines of each edge type.
+    pub edge_styles: BTreeMap<EdgeType, LineStyle>,
+
+    /// Fill colour of the faces without a `fill` attribute.
+    pub face_fill: Color,
+
+    /// Stylesheet of the standalone document.
+    pub stylesheet: Stylesheet,
+
+    /// If true, the styles are written as presentation attributes on
+    /// every element, rather than through CSS classes. The generated
+    /// stylesheet is then left out.
+    pub inline_styles: bool,
+}
+
+/// How the lines are split into separate layers, for example to draw
+/// each layer with a different pen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerSplit {
+    /// One layer per edge type, in the order of
+    /// `SvgOptions::edge_order`.
+    EdgeType,
+
+    /// One layer per user layer, in the order of
+    /// `SvgOptions::layer_order`.
+    UserLayer,
+
+    /// One layer per stroke colour.
+    Color,
+}
+
+/// Names of the layers that are not user layers.
+const DEFAULT_LAYER: &str = "default";
+const RESERVED_LAYERS: [&str; 3] = [DEFAULT_LAYER, "faces", "points"];
+const LAYER_SUFFIX: &str = " (layer)";
+
+/// Name of the layer written for the user layer or edge type `name`.
+/// Names of the reserved layers, and names that already end in the
+/// suffix, get the suffix, so that no two names map to the same
+/// layer.
+pub fn layer_label(name: &str) -> String {
+    if RESERVED_LAYERS.contains(&name) || name.ends_with(LAYER_SUFFIX) {
+        format!("{}{}", name, LAYER_SUFFIX)
+    } else {
+        name.to_string()
+    }
+}
+
+const INKSCAPE_NS: &str = "http://www.inkscape.org/namespaces/inkscape";
+
+/// The stylesheet written into a standalone SVG document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stylesheet {
+    /// Generate the stylesheet from the styles in `SvgOptions`.
+    Generated,
+
+    /// Use this CSS instead of the generated stylesheet.
+    Custom(String),
+
+    /// Import the stylesheet from this URL.
+    Link(String),
+
+    /// Write no stylesheet.
+    None,
+}
+
+impl SvgOptions {
+    /// Options for a page of `width` x `height` pixels.
+    pub fn new(width: f64, height: f64) -> SvgOptions {
+        SvgOptions::with_page(Page::new(width, height, Unit::Px))
+    }
+
+    /// Options for `page`, with the default styles scaled to its
+    /// units.
+    pub fn with_page(page: Page) -> SvgOptions {
+        let k = Unit::Px.convert(1.0, page.unit);
+        let markers = MarkerOptions::default();
+        SvgOptions {
+            page,
+            by_layer: false,
+            edge_order: vec![
+                EdgeType::Culled,
+                EdgeType::Split,
+                EdgeType::Invisible,
+                EdgeType::Hidden,
+                EdgeType::Hatch,
+                EdgeType::Visible,
+            ],
+            edge_names: BTreeMap::new(),
+            layer_order: vec![],
+            inkscape_layers: None,
+            by_object: false,
+            faces: false,
+            shading: None,
+            markers: MarkerOptions {
+                size: markers.size * k,
+                stroke_width: markers.stroke_width * k,
+                ..markers
+            },
+            edge_styles: EdgeType::ALL
+                .iter()
+                .map(|e| (*e, default_edge_style(*e, page.unit)))
+                .collect(),
+            face_fill: Color::WHITE,
+            stylesheet: Stylesheet::Generated,
+            inline_styles: false,
+        }
+    }
+
+    /// All edge types, in paint order.
+    pub fn edge_types_ordered(&self) -> Vec<EdgeType> {
+        let mut order: Vec<EdgeType> = vec![];
+        for e in self.edge_order.iter().chain(EdgeType::ALL.iter()) {
+            if !order.contains(e) {
+                order.push(*e);
+            }
+        }
+        order
+    }
+
+    /// Name of the lines of type `edge`: its entry in `edge_names`,
+    /// or else its class name.
+    pub fn edge_name(&self, edge: EdgeType) -> String {
+        self.edge_names
+            .get(&edge)
+            .cloned()
+            .unwrap_or_else(|| edge.class_name().to_string())
+    }
+
+    /// Style of the lines of type `edge`: its entry in
+    /// `edge_styles`, or else the default style scaled to the page
+    /// units.
+    pub fn edge_style(&self, edge: EdgeType) -> LineStyle {
+        self.edge_styles
+            .get(&edge)
+            .cloned()
+            .unwrap_or_else(|| default_edge_style(edge, self.page.unit))
+    }
+
+    /// Map a point from NDC to output (page) coordinates.
+    pub fn to_output(&self, p: DVec2) -> DVec2 {
+        self.page.to_page(p)
+    }
+
+    /// CSS for the edge types, faces and markers.
+    pub fn css(&self) -> String {
+        let mut css: String = EdgeType::ALL
+            .iter()
+            .map(|e| css_rule(&format!(".{}", e.class_name()), &self.edge_style(*e).properties()))
+            .collect();
+        css += &css_rule(".face", &self.face_properties(None));
+        let m = &self.markers;
+        css += &css_rule(&format!(".{}", m.class), &self.marker_properties(Marker::Circle));
+        css += &css_rule(&format!(".{}.cross", m.class), &self.marker_properties(Marker::Cross));
+        css
+    }
+
+    fn face_properties(&self, fill: Option<Color>) -> Vec<(&'static str, String)> {
+        vec![
+            ("fill", fill.unwrap_or(self.face_fill).to_string()),
+            ("stroke", "none".to_string()),
+        ]
+    }
+
+    fn marker_properties(&self, shape: Marker) -> Vec<(&'static str, String)> {
+        let m = &self.markers;
+        match shape {
+            Marker::Cross => vec![
+                ("fill", "none".to_string()),
+                ("stroke", m.color.to_string()),
+                ("stroke-width", m.stroke_width.to_string()),
+            ],
+            _ => vec![
+                ("fill", m.color.to_string()),
+                ("stroke", "none".to_string()),
+            ],
+        }
+    }
 }
 
 /// Rendering output from the `Renderer`.
 #[derive(Debug, Clone, Default)]
 pub struct RenderPaths {
-    pub points: Vec<DVec2>,
+    pub points: Vec<RenderPoint>,
 
     pub lines: Vec<RenderLine>,
+
+    /// Lines chained together by `merge_lines`.
+    pub polylines: Vec<RenderPolyline>,
+
+    /// Visible regions of the source faces, ordered from back to
+    /// front.
+    pub faces: Vec<RenderFace>,
+
+    /// Attributes of the source primitives, referenced by index from
+    /// each `RenderLine` and `RenderFace`.
+    pub attributes: Vec<Attributes>,
 }
 
 impl RenderPaths {
     /// Return true iff there are no pieces to render, visible or
     /// hidden.
     pub fn is_empty(&self) -> bool {
-        self.points.is_empty() && self.lines.is_empty()
+        self.points.is_empty() && self.lines.is_empty() && self.polylines.is_empty()
     }
 
-    /// Return a copy of this object with only the visible lines and
-    /// points included.
+    /// Return a copy of this object with only the visible lines
+    /// (including hatching) and points included.
     pub fn visible_only(self) -> RenderPaths {
         RenderPaths {
             points: self.points,
@@ -56,103 +547,664 @@ impl RenderPaths {
             lines: self
                 .lines
                 .into_iter()
-                .filter(|rl| rl.edge == EdgeType::Visible)
+                .filter(|rl| matches!(rl.edge, EdgeType::Visible | EdgeType::Hatch))
                 .collect(),
+
+            polylines: self
+                .polylines
+                .into_iter()
+                .filter(|pl| matches!(pl.edge, EdgeType::Visible | EdgeType::Hatch))
+                .collect(),
+
+            faces: self.faces,
+
+            attributes: self.attributes,
         }
     }
 
+    /// Return the rendered points, converted to another point type.
+    pub fn points_as<T: FromDVec2>(&self) -> Vec<T> {
+        self.points.iter().map(|p| p.point_as()).collect()
+    }
+
+    /// Iterate over the lines of a single edge type. Only `lines` are
+    /// searched; after `merge_lines` or `optimize_travel`, use
+    /// `polylines_of_type`.
+    pub fn lines_of_type(&self, edge: EdgeType) -> impl Iterator<Item = &RenderLine> {
+        self.lines.iter().filter(move |l| l.edge == edge)
+    }
+
+    /// Iterate over the lines belonging to a single object. Only
+    /// `lines` are searched; after `merge_lines` or `optimize_travel`,
+    /// use `polylines_of_object`.
+    pub fn lines_of_object(&self, object: ObjectId) -> impl Iterator<Item = &RenderLine> {
+        self.lines.iter().filter(move |l| l.object == Some(object))
+    }
+
+    /// Iterate over the polylines of a single edge type.
+    pub fn polylines_of_type(&self, edge: EdgeType) -> impl Iterator<Item = &RenderPolyline> {
+        self.polylines.iter().filter(move |l| l.edge == edge)
+    }
+
+    /// Iterate over the polylines belonging to a single object.
+    pub fn polylines_of_object(
+        &self,
+        object: ObjectId,
+    ) -> impl Iterator<Item = &RenderPolyline> {
+        self.polylines.iter().filter(move |l| l.object == Some(object))
+    }
+
+    /// Return the attributes of the primitive `line` came from, if
+    /// any.
+    pub fn line_attributes(&self, line: &RenderLine) -> Option<&Attributes> {
+        line.attr.and_then(|i| self.attributes.get(i))
+    }
+
+    /// Return the attributes of the primitive(s) `polyline` came
+    /// from, if any.
+    pub fn polyline_attributes(&self, polyline: &RenderPolyline) -> Option<&Attributes> {
+        polyline.attr.and_then(|i| self.attributes.get(i))
+    }
+
+    /// Cut away the overlap between coincident lines (within
+    /// `tolerance`, in NDC units), keeping the line whose edge type
+    /// comes first in `precedence`. See `dedup::DEFAULT_PRECEDENCE`.
+    ///
+    /// Only `lines` are deduplicated, so call this before
+    /// `merge_lines` or `optimize_travel`, which move the lines into
+    /// `polylines`.
+    pub fn dedup_lines(&mut self, tolerance: f64, precedence: &[EdgeType]) {
+        self.lines = dedup::dedup_lines(&self.lines, tolerance, precedence);
+    }
+
+    /// Chain the lines into polylines, joining end points within
+    /// `tolerance` (in NDC units). The lines are moved into
+    /// `polylines`, keeping the depth of their points; call
+    /// `dedup_lines` first. Afterwards, `lines_of_type` and
+    /// `lines_of_object` find nothing; use their `polylines_of_*`
+    /// counterparts.
+    pub fn merge_lines(&mut self, tolerance: f64) {
+        let lines = std::mem::take(&mut self.lines);
+        self.polylines.extend(polyline::merge_lines(&lines, tolerance));
+    }
+
+    /// Reorder the lines and polylines, and reverse them where it
+    /// helps, to reduce the pen-up travel of a plotter between them.
+    /// The lines are moved into `polylines`.
+    pub fn optimize_travel(&mut self) -> TravelReport {
+        let strokes = self.strokes();
+        let before = TravelStats::of(&strokes);
+        self.lines.clear();
+        self.polylines = travel::order_strokes(strokes);
+        TravelReport {
+            before,
+            after: TravelStats::of(&self.polylines),
+        }
+    }
+
+    /// All lines and polylines, as polylines.
+    pub fn strokes(&self) -> Vec<RenderPolyline> {
+        self.lines
+            .iter()
+            .map(RenderPolyline::from)
+            .chain(self.polylines.iter().cloned())
+            .collect()
+    }
+
+    /// `strokes`, sorted by the paint order of their edge types.
+    pub(crate) fn strokes_in_paint_order(&self, options: &SvgOptions) -> Vec<RenderPolyline> {
+        let order = options.edge_types_ordered();
+        let mut strokes = self.strokes();
+        strokes.sort_by_key(|s| order.iter().position(|e| *e == s.edge));
+        strokes
+    }
+
+    /// Style of `stroke`: the style of its edge type, with the
+    /// stroke colour and width of its attributes, if set.
+    pub(crate) fn stroke_style(&self, stroke: &RenderPolyline, options: &SvgOptions) -> LineStyle {
+        let mut style = options.edge_style(stroke.edge);
+        if let Some(attr) = self.polyline_attributes(stroke) {
+            style.stroke = attr.stroke.unwrap_or(style.stroke);
+            style.width = attr.stroke_width.unwrap_or(style.width);
+        }
+        style
+    }
+
+    /// Fill colour of `face`, from its attributes and the shading,
+    /// if any. `None` means the default `face_fill`.
+    pub(crate) fn face_color(&self, face: &RenderFace, options: &SvgOptions) -> Option<Color> {
+        let fill = self.face_attributes(face).and_then(|a| a.fill);
+        match &options.shading {
+            Some(s) => Some(s.fill(face, fill)),
+            None => fill,
+        }
+    }
+
+    /// Return the attributes of the primitive `face` came from, if
+    /// any.
+    pub fn face_attributes(&self, face: &RenderFace) -> Option<&Attributes> {
+        face.attr.and_then(|i| self.attributes.get(i))
+    }
+
+    /// Hatch each visible face according to its tone, adding the
+    /// hatching as lines of type `EdgeType::Hatch`.
+    pub fn add_hatching(&mut self, options: &HatchOptions) {
+        let mut hatches = vec![];
+        for face in &self.faces {
+            for [p0, p1] in options.hatch_face(face, self.face_attributes(face)) {
+                hatches.push(RenderLine {
+                    depth: [face.depth; 2],
+                    attr: face.attr,
+                    id: face.id,
+                    object: face.object,
+                    ..RenderLine::new(p0, p1, EdgeType::Hatch)
+                });
+            }
+        }
+        self.lines.extend(hatches);
+    }
+
+    /// Stipple each visible face according to its tone, adding the
+    /// dots to `points`.
+    pub fn add_stippling(&mut self, options: &StippleOptions) {
+        let mut dots = vec![];
+        for face in &self.faces {
+            let points = options.stipple_face(face, self.face_attributes(face));
+            dots.extend(points.into_iter().map(|p| RenderPoint {
+                depth: face.depth,
+                attr: face.attr,
+                id: face.id,
+                object: face.object,
+                ..RenderPoint::new(p)
+            }));
+        }
+        self.points.extend(dots);
+    }
+
     /// Render a complete svg document from this structure.
     pub fn as_standalone_svg(self: &RenderPaths, options: &SvgOptions) -> Document {
-        let half_width = options.width * 0.5;
-        let half_height = options.height * 0.5;
+        let page = &options.page;
         let mut d = Document::new()
-            .set("width", format!("{}", half_width * 2.0))
-            .set("height", format!("{}", half_height * 2.0))
-            .add(Style::new(
-                ".visible { stroke-width: 0.005; fill: none; stroke: #444444; }
-.hidden { stroke-width: 0.002; fill: none; stroke: #2222cc; stroke-dasharray: 0.01 0.005; }
-.invisible { stroke-width: 0.001; fill: none; stroke: #aaaaaa; stroke-dasharray: 0.001 0.001; }
-.split { stroke-width: 0.001; fill: none; stroke: #22cc22; stroke-dasharray: 0.002 0.002; }
-.culled { stroke-width: 0.001; fill: none; stroke: #cc2222; stroke-dasharray: 0.005 0.005; }",
-            ));
-
-        let mut g = Group::new().set(
-            "transform",
-            format!(
-                "translate({} {}) scale({} -{})",
-                half_width, half_height, half_width, half_height
-            ),
-        );
+            .set("width", format!("{}{}", page.width, page.unit))
+            .set("height", format!("{}{}", page.height, page.unit))
+            .set("viewBox", page.view_box());
+        if options.inkscape_layers.is_some() {
+            d = d.set("xmlns:inkscape", INKSCAPE_NS);
+        }
+        let css = match &options.stylesheet {
+            Stylesheet::Generated if options.inline_styles => None,
+            Stylesheet::Generated => Some(options.css()),
+            Stylesheet::Custom(css) => Some(css.clone()),
+            Stylesheet::Link(href) => Some(format!("@import url(\"{}\");", href)),
+            Stylesheet::None => None,
+        };
+        if let Some(css) = css {
+            d = d.add(Style::new(css));
+        }
+
+        match options.inkscape_layers {
+            // Inkscape only treats top-level groups as layers.
+            Some(split) => self
+                .inkscape_layers(split, options)
+                .into_iter()
+                .fold(d, |d, layer| d.add(layer)),
+            None => d.add(self.as_page_group(options)),
+        }
+    }
+
+    /// Add a `<line>` (or `<polyline>`, for more than two points) to
+    /// `group`.
+    fn add_line(
+        &self,
+        group: Group,
+        line: &RenderPolyline,
+        class: Option<&str>,
+        options: &SvgOptions,
+    ) -> Group {
+        let mut props = vec![];
+        if options.inline_styles {
+            props = options.edge_style(line.edge).properties();
+        }
+        let attr = self.polyline_attributes(line);
+        let classes: Vec<&str> = class
+            .into_iter()
+            .chain(attr.and_then(|a| a.class.as_deref()))
+            .collect();
+        if !classes.is_empty() {
+            props.push(("class", classes.join(" ")));
+        }
+        // The per-primitive stroke goes into a `style` attribute, as
+        // the stylesheet rules of the edge types would override
+        // presentation attributes.
+        if let Some(attr) = attr {
+            let mut overrides = vec![];
+            if let Some(stroke) = attr.stroke {
+                overrides.push(("stroke", stroke.to_string()));
+            }
+            if let Some(width) = attr.stroke_width {
+                overrides.push(("stroke-width", width.to_string()));
+            }
+            if !overrides.is_empty() {
+                props.push(("style", inline_style(&overrides)));
+            }
+        }
+
+        let points: Vec<DVec2> = line.points.iter().map(|p| options.to_output(*p)).collect();
+        if let [p0, p1] = points[..] {
+            let elem = element::Line::new()
+                .set("x1", p0.x)
+                .set("y1", p0.y)
+                .set("x2", p1.x)
+                .set("y2", p1.y);
+            group.add(with_properties(elem, props))
+        } else {
+            let points: Vec<String> =
+                points.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+            let elem = element::Polyline::new().set("points", points.join(" "));
+            group.add(with_properties(elem, props))
+        }
+    }
 
-        g = g.add(self.as_svg_group(options));
-        d = d.add(g);
+    /// Add one group per edge type to `g`, containing `lines`.
+    fn add_edge_groups(
+        &self,
+        mut g: Group,
+        lines: &[&RenderPolyline],
+        options: &SvgOptions,
+    ) -> Group {
+        for edge_type in options.edge_types_ordered() {
+            let lines: Vec<&RenderPolyline> =
+                lines.iter().copied().filter(|l| l.edge == edge_type).collect();
+            if lines.is_empty() {
+                continue;
+            }
+            let mut group = Group::new().set("class", edge_type.class_name());
+            if let Some(name) = options.edge_names.get(&edge_type) {
+                group = group.set("data-name", name.as_str());
+            }
+            for line in &lines {
+                group = self.add_line(group, line, None, options);
+            }
+            g = g.add(group);
+        }
+        g
+    }
 
-        d
+    /// Add a path for each visible face to `g`, from back to front.
+    fn add_faces(&self, mut g: Group, options: &SvgOptions) -> Group {
+        for face in &self.faces {
+            let d: String = face
+                .loops
+                .iter()
+                .map(|l| {
+                    let points: Vec<String> = l
+                        .iter()
+                        .map(|p| options.to_output(*p))
+                        .map(|p| format!("{},{}", p.x, p.y))
+                        .collect();
+                    format!("M{}Z", points.join(" L"))
+                })
+                .collect();
+            let attr = self.face_attributes(face);
+            let classes: Vec<&str> = std::iter::once("face")
+                .chain(attr.and_then(|a| a.class.as_deref()))
+                .collect();
+            let mut elem = element::Path::new()
+                .set("d", d)
+                .set("class", classes.join(" "));
+            let fill = self.face_color(face, options);
+            if options.inline_styles {
+                elem = with_properties(elem, options.face_properties(fill));
+            } else if let Some(fill) = fill {
+                // As for lines, the `.face` rule would override a
+                // presentation attribute.
+                elem = elem.set("style", inline_style(&[("fill", fill.to_string())]));
+            }
+            g = g.add(elem);
+        }
+        g
     }
 
-    fn add_line(group: Group, line: &RenderLine, class: Option<&str>) -> Group {
-	let mut line = element::Line::new()
-                    .set("x1", line.points[0].x)
-                    .set("y1", line.points[0].y)
-                    .set("x2", line.points[1].x)
-                    .set("y2", line.points[1].y);
-	if let Some(class_name) = class {
-	    line = line.set("class", class_name);
-	}
-	group.add(line)
+    /// Split the lines and polylines into named layers, in paint
+    /// order. With `LayerSplit::UserLayer`, lines without a user layer
+    /// go into a layer named "default", painted first as with
+    /// `by_layer`. User layer and edge names that would clash with
+    /// the "default", "faces" or "points" layers get a " (layer)"
+    /// suffix, see `layer_label`.
+    pub fn line_layers(
+        &self,
+        split: LayerSplit,
+        options: &SvgOptions,
+    ) -> Vec<(String, Vec<RenderPolyline>)> {
+        let strokes = self.strokes();
+        let mut layers: Vec<(String, Vec<RenderPolyline>)> = vec![];
+        let mut add = |name: String, line: Option<&RenderPolyline>| {
+            let i = match layers.iter().position(|(n, _)| *n == name) {
+                Some(i) => i,
+                None => {
+                    layers.push((name, vec![]));
+                    layers.len() - 1
+                }
+            };
+            layers[i].1.extend(line.cloned());
+        };
+        match split {
+            LayerSplit::EdgeType => {
+                for e in options.edge_types_ordered() {
+                    for line in strokes.iter().filter(|l| l.edge == e) {
+                        add(layer_label(&options.edge_name(e)), Some(line));
+                    }
+                }
+            }
+            LayerSplit::UserLayer => {
+                let label = |name: Option<&str>| match name {
+                    Some(name) => layer_label(name),
+                    None => DEFAULT_LAYER.to_string(),
+                };
+                // establish the order of the layers first, the same
+                // as in `add_lines`
+                for name in std::iter::once(None)
+                    .chain(options.layer_order.iter().map(|l| Some(l.as_str())))
+                    .chain(self.attributes.iter().map(|a| a.layer.as_deref()))
+                {
+                    add(label(name), None);
+                }
+                for line in &strokes {
+                    let name = self.polyline_attributes(line).and_then(|a| a.layer.as_deref());
+                    add(label(name), Some(line));
+                }
+            }
+            LayerSplit::Color => {
+                for e in options.edge_types_ordered() {
+                    let style = options.edge_style(e);
+                    for line in strokes.iter().filter(|l| l.edge == e) {
+                        let stroke = self.polyline_attributes(line).and_then(|a| a.stroke);
+                        add(stroke.unwrap_or(style.stroke).to_string(), Some(line));
+                    }
+                }
+            }
+        }
+        layers.retain(|(_, lines)| !lines.is_empty());
+        layers
     }
 
+    /// Group of the faces, lines and points in NDC, with y pointing
+    /// up, for embedding into another document. The elements are
+    /// drawn as by `as_page_group`, under a transform from the page
+    /// back to NDC, so line widths and marker sizes keep their size
+    /// relative to the page.
     pub fn as_svg_group(&self, options: &SvgOptions) -> Group {
-        let mut g = Group::new();
-	let mut lines_by_type: HashMap<EdgeType, Vec<&RenderLine>> = HashMap::new();
-
-	if options.by_layer {
-	    // Group the lines by edge type, and render each group. 
-	    for line in &self.lines {
-		lines_by_type.entry(line.edge).or_default().push(&line);
-	    }
-	    
-	    for (edge_type, lines) in lines_by_type {
-		let mut group = Group::new().set("class", edge_type.class_name());
-		for line in &lines {
-		    group = Self::add_line(group, line, None);
-		}
-		g = g.add(group);
-	    }
-	} else {
-            for line in &self.lines {
-		g = Self::add_line(g, line, Some(line.edge.class_name()));
-            }
-	}
+        let (origin, size) = options.page.drawing_area();
+        if size.x <= 0.0 || size.y <= 0.0 {
+            // Nothing is drawn on a page without a drawing area.
+            return Group::new();
+        }
+        let (sx, sy) = (2.0 / size.x, -2.0 / size.y);
+        Group::new()
+            .set(
+                "transform",
+                format!(
+                    "matrix({} 0 0 {} {} {})",
+                    sx,
+                    sy,
+                    -origin.x * sx - 1.0,
+                    -origin.y * sy + 1.0
+                ),
+            )
+            .add(self.as_page_group(options))
+    }
+
+    /// Group of the faces, lines and points in page coordinates, as
+    /// written by `as_standalone_svg`. With `inkscape_layers`, the
+    /// layers are nested in the group, and are only recognised as
+    /// layers once moved to the top level of a document.
+    pub fn as_page_group(&self, options: &SvgOptions) -> Group {
+        if let Some(split) = options.inkscape_layers {
+            return self
+                .inkscape_layers(split, options)
+                .into_iter()
+                .fold(Group::new(), |g, layer| g.add(layer));
+        }
+
+        // Faces always go underneath all of the lines, so that they
+        // never cover the lines of another object.
+        let g = if options.faces {
+            Group::new().add(self.add_faces(Group::new().set("class", "faces"), options))
+        } else {
+            Group::new()
+        };
+
+        let strokes = self.strokes();
+        let lines: Vec<&RenderPolyline> = strokes.iter().collect();
+        let g = if options.by_object {
+            // Lines without an object go directly into the top-level
+            // group.
+            let mut lines_by_object: BTreeMap<Option<ObjectId>, Vec<&RenderPolyline>> =
+                BTreeMap::new();
+            for line in &strokes {
+                lines_by_object.entry(line.object).or_default().push(line);
+            }
+
+            let mut g = g;
+            for (object, lines) in lines_by_object {
+                match object {
+                    Some(id) => {
+                        let group = Group::new().set("id", format!("object-{}", id));
+                        g = g.add(self.add_lines(group, &lines, options));
+                    }
+                    None => {
+                        g = self.add_lines(g, &lines, options);
+                    }
+                }
+            }
+            g
+        } else {
+            self.add_lines(g, &lines, options)
+        };
+
+        if self.points.is_empty() {
+            g
+        } else {
+            g.add(self.add_points(Group::new().set("class", "points"), options))
+        }
+    }
+
+    /// The faces, lines and points as Inkscape layers, in paint order.
+    fn inkscape_layers(&self, split: LayerSplit, options: &SvgOptions) -> Vec<Group> {
+        let mut layers: Vec<(String, Group)> = vec![];
+        if options.faces && !self.faces.is_empty() {
+            let g = self.add_faces(Group::new().set("class", "faces"), options);
+            layers.push(("faces".to_string(), g));
+        }
+        for (name, lines) in self.line_layers(split, options) {
+            let mut g = Group::new();
+            for line in &lines {
+                g = self.add_line(g, line, Some(line.edge.class_name()), options);
+            }
+            layers.push((name, g));
+        }
+        if !self.points.is_empty() {
+            let g = self.add_points(Group::new().set("class", "points"), options);
+            layers.push(("points".to_string(), g));
+        }
+
+        layers
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, layer))| {
+                layer
+                    .set("id", format!("layer{}", i + 1))
+                    .set("inkscape:groupmode", "layer")
+                    .set("inkscape:label", name)
+            })
+            .collect()
+    }
+
+    /// Add a marker for each point to `g`.
+    fn add_points(&self, mut g: Group, options: &SvgOptions) -> Group {
+        let markers = &options.markers;
+        for point in &self.points {
+            let (p, r) = (options.to_output(point.point), markers.point_size(point));
+            let attr = point.attr.and_then(|i| self.attributes.get(i));
+            let classes: Vec<&str> = [markers.class.as_str(), markers.shape.class_name()]
+                .iter()
+                .copied()
+                .chain(attr.and_then(|a| a.class.as_deref()))
+                .collect();
+            let mut props = vec![("class", classes.join(" "))];
+            if options.inline_styles {
+                props.extend(options.marker_properties(markers.shape));
+            }
+            g = match markers.shape {
+                Marker::Circle => g.add(with_properties(
+                    element::Circle::new()
+                        .set("cx", p.x)
+                        .set("cy", p.y)
+                        .set("r", r),
+                    props,
+                )),
+                Marker::Square => g.add(with_properties(
+                    element::Rectangle::new()
+                        .set("x", p.x - r)
+                        .set("y", p.y - r)
+                        .set("width", 2.0 * r)
+                        .set("height", 2.0 * r),
+                    props,
+                )),
+                Marker::Cross => g.add(with_properties(
+                    element::Path::new().set(
+                        "d",
+                        format!(
+                            "M{},{} L{},{} M{},{} L{},{}",
+                            p.x - r,
+                            p.y - r,
+                            p.x + r,
+                            p.y + r,
+                            p.x - r,
+                            p.y + r,
+                            p.x + r,
+                            p.y - r
+                        ),
+                    ),
+                    props,
+                )),
+            };
+        }
+        g
+    }
+
+    fn add_lines(&self, mut g: Group, lines: &[&RenderPolyline], options: &SvgOptions) -> Group {
+        if options.by_layer {
+            // Group the lines by user layer, and then by edge type,
+            // and render each group. Lines without a layer go
+            // directly into the top-level group.
+            let mut layers: Vec<Option<&str>> = vec![None];
+            for layer in options
+                .layer_order
+                .iter()
+                .map(|l| Some(l.as_str()))
+                .chain(self.attributes.iter().map(|a| a.layer.as_deref()))
+            {
+                if !layers.contains(&layer) {
+                    layers.push(layer);
+                }
+            }
+            let mut lines_by_layer: HashMap<Option<&str>, Vec<&RenderPolyline>> = HashMap::new();
+            for line in lines {
+                let layer = self.polyline_attributes(line).and_then(|a| a.layer.as_deref());
+                lines_by_layer.entry(layer).or_default().push(line);
+            }
+
+            for layer in layers {
+                let lines = match lines_by_layer.get(&layer) {
+                    Some(lines) => lines,
+                    None => continue,
+                };
+                match layer {
+                    Some(name) => {
+                        let group = Group::new().set("data-layer", name);
+                        g = g.add(self.add_edge_groups(group, lines, options));
+                    }
+                    None => {
+                        g = self.add_edge_groups(g, lines, options);
+                    }
+                }
+            }
+        } else {
+            for line in lines {
+                g = self.add_line(g, line, Some(line.edge.class_name()), options);
+            }
+        }
         g
     }
 }
 
+/// `LineStyle::for_edge`, converted from pixels to `unit`.
+fn default_edge_style(edge: EdgeType, unit: Unit) -> LineStyle {
+    let k = Unit::Px.convert(1.0, unit);
+    let mut s = LineStyle::for_edge(edge);
+    s.width *= k;
+    s.dash.iter_mut().for_each(|d| *d *= k);
+    s
+}
+
+/// Format properties as the value of a `style` attribute.
+fn inline_style(props: &[(&str, String)]) -> String {
+    let decls: Vec<String> = props.iter().map(|(k, v)| format!("{}:{}", k, v)).collect();
+    decls.join(";")
+}
+
+/// Set each of the attributes `props` on `elem`.
+fn with_properties<T: Node>(mut elem: T, props: Vec<(&'static str, String)>) -> T {
+    for (k, v) in props {
+        elem.assign(k, v);
+    }
+    elem
+}
+
 impl<'a> std::iter::FromIterator<&'a ZsortPrim> for RenderPaths {
     fn from_iter<T>(iter: T) -> Self
     where
         T: IntoIterator<Item = &'a ZsortPrim>,
     {
         let mut rp = RenderPaths::default();
+        let mut fragments: BTreeMap<PrimId, Vec<&ZsortPrim>> = BTreeMap::new();
         for prim in iter {
+            if let Primitive::Triangle { ref tri } = prim.p {
+                if !tri.is_hidden() && !tri.is_culled() {
+                    match prim.id {
+                        Some(id) => fragments.entry(id).or_default().push(prim),
+                        None => rp.faces.extend(RenderFace::from_fragments(&[prim])),
+                    }
+                }
+            }
             match prim.p {
                 Primitive::Point { point } => {
-                    rp.points.push(point.xy());
+                    rp.points.push(RenderPoint::from_ndc(point).tagged(prim));
                 }
                 Primitive::Line { points } => {
-                    rp.lines.push(RenderLine::new(
-                        points[0].xy(),
-                        points[1].xy(),
-                        EdgeType::Visible,
-                    ));
+                    rp.lines.push(
+                        RenderLine::from_ndc(points[0], points[1], EdgeType::Visible)
+                            .tagged(prim),
+                    );
                 }
                 Primitive::Triangle { tri: Tri { p, e } } => {
-                    rp.lines.push(RenderLine::new(p[0].xy(), p[1].xy(), e[0]));
-                    rp.lines.push(RenderLine::new(p[1].xy(), p[2].xy(), e[1]));
-                    rp.lines.push(RenderLine::new(p[2].xy(), p[0].xy(), e[2]));
+                    rp.lines
+                        .push(RenderLine::from_ndc(p[0], p[1], e[0]).tagged(prim));
+                    rp.lines
+                        .push(RenderLine::from_ndc(p[1], p[2], e[1]).tagged(prim));
+                    rp.lines
+                        .push(RenderLine::from_ndc(p[2], p[0], e[2]).tagged(prim));
                 }
             }
         }
+        rp.faces.extend(
+            fragments
+                .values()
+                .filter_map(|frags| RenderFace::from_fragments(frags)),
+        );
+        rp.faces
+            .sort_by(|a, b| b.depth.partial_cmp(&a.depth).unwrap());
         rp
     }
 }
@@ -1,21 +1,33 @@
 use na::Matrix4;
 
 use crate::common::*;
+use crate::convert::{IntoDVec3, ToDMat4};
 use crate::intersect::{split_triangle_by_segment, triangle_in_triangle_2d, SplitResult};
+use crate::mesh::Mesh;
 use crate::primitive::*;
 use crate::render_paths::RenderPaths;
+use crate::scene::{Aabb, Node};
+use crate::style::Attributes;
 use itertools::Itertools;
 use std::collections::binary_heap::BinaryHeap;
+use std::sync::Arc;
 
 pub struct Renderer {
     clip: Matrix4<f64>,
-    input_primitives: Vec<Primitive>,
+    input_primitives: Vec<InputPrim>,
+    instances: Vec<Instance>,
+    scenes: Vec<SceneRoot>,
+    num_ids: usize,
+    attributes: Vec<Attributes>,
+    current_attr: Option<usize>,
+    current_object: Option<ObjectId>,
     depth_range: [f64; 2],
     cull_mode: CullMode,
     show_hidden: bool,
     debug: bool,
 }
 
+#[allow(dead_code)]
 trait VDebug {
     fn na_dbg(&self) -> String;
 }
@@ -41,6 +53,55 @@ impl VDebug for Tri {
     }
 }
 
+/// A primitive as added to the renderer, along with its
+/// (unprojected) metadata.
+#[derive(Debug, Clone)]
+struct InputPrim {
+    prim: Primitive,
+    id: PrimId,
+    attr: Option<usize>,
+    object: Option<ObjectId>,
+}
+
+/// A `Mesh` placed in the scene with a model transform.
+#[derive(Debug, Clone)]
+struct Instance {
+    mesh: Arc<Mesh>,
+    model: Matrix4<f64>,
+    first_id: PrimId,
+    attr: Option<usize>,
+    object: Option<ObjectId>,
+}
+
+/// The root `Node` of a scene added to the renderer.
+#[derive(Debug, Clone)]
+struct SceneRoot {
+    node: Node,
+    first_id: PrimId,
+    attr: Option<usize>,
+    object: Option<ObjectId>,
+}
+
+/// Ids and styling of a source primitive. For scene nodes, `id` is
+/// the first id of the node's subtree.
+#[derive(Debug, Clone, Copy)]
+struct PrimTag {
+    id: PrimId,
+    attr: Option<usize>,
+    object: Option<ObjectId>,
+}
+
+/// Return the index of `attr` in `attributes`, adding it if necessary.
+fn intern_attributes(attributes: &mut Vec<Attributes>, attr: Attributes) -> usize {
+    match attributes.iter().position(|x| *x == attr) {
+        Some(i) => i,
+        None => {
+            attributes.push(attr);
+            attributes.len() - 1
+        }
+    }
+}
+
 /// Determine how clockwise-wound triangles should be handled.
 #[derive(Debug, Clone, Copy)]
 pub enum CullMode {
@@ -55,45 +116,89 @@ pub enum CullMode {
 }
 
 impl Renderer {
-    pub fn new(c: &Matrix4<f64>) -> Renderer {
+    /// Create a renderer with the clip (projection * view) matrix `c`.
+    pub fn new(c: &impl ToDMat4) -> Renderer {
         Renderer {
-            clip: *c,
+            clip: c.to_dmat4(),
             input_primitives: vec![],
+            instances: vec![],
+            scenes: vec![],
+            num_ids: 0,
+            attributes: vec![],
+            current_attr: None,
+            current_object: None,
             depth_range: [-1.0, 1.0],
             cull_mode: CullMode::Discard,
             show_hidden: false,
             debug: false,
         }
     }
+    #[deprecated(note = "`Renderer::new` accepts `Matrix4<f32>` directly")]
     pub fn new_f32(c: &Matrix4<f32>) -> Renderer {
-	let clip64 = glm::DMat4::from_vec(c.as_slice().iter().map(|x| *x as f64).collect());
-	Renderer::new(&clip64)
+        Renderer::new(c)
+    }
+
+    /// Set the attributes applied to every primitive added from now
+    /// on. Passing `None` reverts to unstyled primitives.
+    pub fn set_attributes(&mut self, attr: impl Into<Option<Attributes>>) {
+        self.current_attr = attr
+            .into()
+            .map(|a| intern_attributes(&mut self.attributes, a));
+    }
+
+    /// Set the object id attached to every primitive added from now
+    /// on. Passing `None` detaches subsequent primitives from any
+    /// object.
+    pub fn set_object(&mut self, object: impl Into<Option<ObjectId>>) {
+        self.current_object = object.into();
+    }
+
+    fn next_id(&mut self) -> PrimId {
+        self.num_ids += 1;
+        self.num_ids - 1
+    }
+
+    fn push_prim(&mut self, prim: Primitive, id: PrimId) {
+        self.input_primitives.push(InputPrim {
+            prim,
+            id,
+            attr: self.current_attr,
+            object: self.current_object,
+        });
     }
 
-    /// add a primitive to the render list
-    pub fn add_prim(&mut self, p: Primitive) {
-        self.input_primitives.push(p);
+    /// add a primitive to the render list, returning its id
+    pub fn add_prim(&mut self, p: Primitive) -> PrimId {
+        let id = self.next_id();
+        self.push_prim(p, id);
+        id
     }
 
     /// Add a point to the list, given the primitive.
-    pub fn add_point(&mut self, p: DVec3) {
-        self.add_prim(Primitive::Point { point: p.push(1.0) });
+    pub fn add_point(&mut self, p: impl IntoDVec3) -> PrimId {
+        self.add_prim(Primitive::Point {
+            point: p.into_dvec3().push(1.0),
+        })
     }
 
-    pub fn add_line(&mut self, p0: DVec3, p1: DVec3) {
+    pub fn add_line(&mut self, p0: impl IntoDVec3, p1: impl IntoDVec3) -> PrimId {
         self.add_prim(Primitive::Line {
-            points: [p0.push(1.0), p1.push(1.0)],
-        });
+            points: [p0.into_dvec3().push(1.0), p1.into_dvec3().push(1.0)],
+        })
     }
 
     /// Add a triangle to the renderer, with all visible edges.
-    pub fn add_triangle(&mut self, p0: DVec3, p1: DVec3, p2: DVec3) {
+    pub fn add_triangle<V: IntoDVec3>(&mut self, p0: V, p1: V, p2: V) -> PrimId {
         self.add_prim(Primitive::Triangle {
             tri: Tri {
-                p: [p0.push(1.0), p1.push(1.0), p2.push(1.0)],
+                p: [
+                    p0.into_dvec3().push(1.0),
+                    p1.into_dvec3().push(1.0),
+                    p2.into_dvec3().push(1.0),
+                ],
                 e: [EdgeType::Visible; 3],
             },
-        });
+        })
     }
 
     /// Add a polygon to the list, with all outside edges visible.
@@ -101,74 +206,170 @@ impl Renderer {
     /// # Remarks
     ///
     /// Polygons are internally translated into a triangles as a
-    /// triangle fan. The inner edges are marked as Invisible.
-    pub fn add_polygon(&mut self, p: &[DVec3]) {
-        for i in 0..p.len() - 2 {
-            let e0 = if i == 0 {
-                EdgeType::Visible
-            } else {
-                EdgeType::Invisible
-            };
-            let e2 = if i == p.len() - 3 {
-                EdgeType::Visible
-            } else {
-                EdgeType::Invisible
-            };
-            self.add_prim(Primitive::Triangle {
-                tri: Tri {
-                    p: [p[0].push(1.0), p[i + 1].push(1.0), p[i + 2].push(1.0)],
-                    e: [e0, EdgeType::Visible, e2],
-                },
-            });
+    /// triangle fan. The inner edges are marked as Invisible. All of
+    /// the triangles share the returned id.
+    pub fn add_polygon<V: IntoDVec3 + Copy>(&mut self, p: &[V]) -> PrimId {
+        let points: Vec<_> = p.iter().map(|v| v.into_dvec3().push(1.0)).collect();
+        let id = self.next_id();
+        for tri in Tri::fan(&points) {
+            self.push_prim(Primitive::Triangle { tri }, id);
         }
+        id
     }
 
     /// Similar to add_polygon, but for `Vec3`s instead of `DVec3`s.
-    pub fn add_polygon_f32(&mut self, p: &[Vec3]) {
-        for i in 0..p.len() - 2 {
-            let e0 = if i == 0 {
-                EdgeType::Visible
-            } else {
-                EdgeType::Invisible
-            };
-            let e2 = if i == p.len() - 3 {
-                EdgeType::Visible
-            } else {
-                EdgeType::Invisible
-            };
-	    let p0 = vec4(p[0].x as f64, p[0].y as f64, p[0].z as f64, 1.0);
-	    let p1 = vec4(p[i + 1].x as f64, p[i + 1].y as f64, p[i + 1].z as f64, 1.0);
-	    let p2 = vec4(p[i + 2].x as f64, p[i + 2].y as f64, p[i + 2].z as f64, 1.0);
-            self.add_prim(Primitive::Triangle {
-                tri: Tri {
-                    p: [p0, p1, p2],
-                    e: [e0, EdgeType::Visible, e2],
-                },
+    #[deprecated(note = "`Renderer::add_polygon` accepts `Vec3` directly")]
+    pub fn add_polygon_f32(&mut self, p: &[Vec3]) -> PrimId {
+        self.add_polygon(p)
+    }
+
+    /// Add an instance of `mesh`, transformed by the `model` matrix.
+    ///
+    /// The mesh geometry is shared between all of its instances. The
+    /// primitives of the instance get the ids `base + local_id`, where
+    /// `base` is the returned id and `local_id` is the id returned by
+    /// the `Mesh::add_*` methods.
+    pub fn add_instance(&mut self, mesh: &Arc<Mesh>, model: &impl ToDMat4) -> PrimId {
+        let first_id = self.num_ids;
+        self.num_ids += mesh.num_ids();
+        self.instances.push(Instance {
+            mesh: mesh.clone(),
+            model: model.to_dmat4(),
+            first_id,
+            attr: self.current_attr,
+            object: self.current_object,
+        });
+        first_id
+    }
+
+    /// Add a scene hierarchy rooted at `node`.
+    ///
+    /// The tree is flattened into mesh instances at render time. Ids
+    /// are assigned to the primitives of the tree in depth-first
+    /// order, starting at the returned id: the meshes of a node come
+    /// before its children. The current attributes and object id
+    /// apply to any node that doesn't set its own.
+    pub fn add_node(&mut self, mut node: Node) -> PrimId {
+        node.fill_bounds();
+        let first_id = self.num_ids;
+        self.num_ids += node.num_ids();
+        self.scenes.push(SceneRoot {
+            node,
+            first_id,
+            attr: self.current_attr,
+            object: self.current_object,
+        });
+        first_id
+    }
+
+    /// Flatten the subtree of `node` into `instances`, skipping any
+    /// nodes that are hidden or outside of the view frustum.
+    fn flatten_node(
+        &self,
+        node: &Node,
+        parent: &Matrix4<f64>,
+        tag: PrimTag,
+        attributes: &mut Vec<Attributes>,
+        instances: &mut Vec<Instance>,
+    ) {
+        if !node.visible {
+            return;
+        }
+        let model = parent * node.transform;
+        if let Some(bounds) = &node.bounds {
+            if self.bounds_culled(&(self.clip * model), bounds) {
+                return;
+            }
+        }
+
+        let mut tag = PrimTag {
+            attr: match &node.attributes {
+                Some(a) => Some(intern_attributes(attributes, a.clone())),
+                None => tag.attr,
+            },
+            object: node.object.or(tag.object),
+            ..tag
+        };
+        for mesh in &node.meshes {
+            instances.push(Instance {
+                mesh: mesh.clone(),
+                model,
+                first_id: tag.id,
+                attr: tag.attr,
+                object: tag.object,
             });
+            tag.id += mesh.num_ids();
+        }
+        for child in &node.children {
+            self.flatten_node(child, &model, tag, attributes, instances);
+            tag.id += child.num_ids();
+        }
+    }
+
+    /// Project an input primitive into NDC with the `clip` matrix, and
+    /// tag it with the ids and world-space normal of its source.
+    /// `model` transforms the primitive into world space.
+    fn project_input(
+        model: &Matrix4<f64>,
+        clip: &Matrix4<f64>,
+        prim: &Primitive,
+        tag: PrimTag,
+    ) -> ZsortPrim {
+        let mut zp = ZsortPrim::from(Self::proj_prim(clip, prim));
+        zp.id = Some(tag.id);
+        zp.attr = tag.attr;
+        zp.object = tag.object;
+        if let Primitive::Triangle { tri } = prim {
+            let p: Vec<DVec3> = tri.p.iter().map(|v| (model * v).xyz()).collect();
+            zp.normal = (p[1] - p[0]).cross(&(p[2] - p[0])).try_normalize(0.0);
         }
+        zp
+    }
+
+    /// Return true iff the bounding box `bounds`, transformed by
+    /// `clip`, is entirely outside of a single frustum plane.
+    ///
+    /// The test is done in homogeneous clip space, so that it is
+    /// still valid for boxes that extend behind the eye.
+    fn bounds_culled(&self, clip: &Matrix4<f64>, bounds: &Aabb) -> bool {
+        let points: Vec<DVec4> = bounds
+            .corners()
+            .iter()
+            .map(|c| clip * c.push(1.0))
+            .collect();
+        points.iter().all(|v| v.x < -v.w)
+            || points.iter().all(|v| v.x > v.w)
+            || points.iter().all(|v| v.y < -v.w)
+            || points.iter().all(|v| v.y > v.w)
+            || points.iter().all(|v| v.z < self.depth_range[0] * v.w)
+            || points.iter().all(|v| v.z > self.depth_range[1] * v.w)
     }
 
-    /// Project the primitive as a whole.
-    fn proj_prim(&self, prim: &Primitive) -> Primitive {
+    /// Project the primitive as a whole, using the `clip` matrix.
+    fn proj_prim(clip: &Matrix4<f64>, prim: &Primitive) -> Primitive {
         match prim {
             Primitive::Point { point } => Primitive::Point {
-                point: self.proj(&point),
+                point: Self::proj(clip, point),
             },
             Primitive::Line { points } => Primitive::Line {
-                points: [self.proj(&points[0]), self.proj(&points[1])],
+                points: [Self::proj(clip, &points[0]), Self::proj(clip, &points[1])],
             },
             Primitive::Triangle { tri: Tri { p, e } } => Primitive::Triangle {
                 tri: Tri {
-                    p: [self.proj(&p[0]), self.proj(&p[1]), self.proj(&p[2])],
-                    e: e.clone(),
+                    p: [
+                        Self::proj(clip, &p[0]),
+                        Self::proj(clip, &p[1]),
+                        Self::proj(clip, &p[2]),
+                    ],
+                    e: *e,
                 },
             },
         }
     }
 
     /// Project a point into NDC.
-    fn proj(&self, p: &DVec4) -> DVec4 {
-        let r = self.clip * p;
+    fn proj(clip: &Matrix4<f64>, p: &DVec4) -> DVec4 {
+        let r = clip * p;
         vec4(r.x / r.w, r.y / r.w, r.z / r.w, r.w)
     }
 
@@ -197,34 +398,71 @@ impl Renderer {
     }
 
     pub fn render(&self) -> RenderPaths {
-        let culled: Vec<_> = self
-            .input_primitives
-            .iter()
-            // project the primitives into clip space
-            .map(|p| self.proj_prim(p))
+        let mut attributes = self.attributes.clone();
+        let mut scene_instances = vec![];
+        for root in &self.scenes {
+            let tag = PrimTag {
+                id: root.first_id,
+                attr: root.attr,
+                object: root.object,
+            };
+            self.flatten_node(
+                &root.node,
+                &Matrix4::identity(),
+                tag,
+                &mut attributes,
+                &mut scene_instances,
+            );
+        }
+
+        let identity = Matrix4::identity();
+        let direct = self.input_primitives.iter().map(|ip| {
+            let tag = PrimTag {
+                id: ip.id,
+                attr: ip.attr,
+                object: ip.object,
+            };
+            Self::project_input(&identity, &self.clip, &ip.prim, tag)
+        });
+        let instanced = self.instances.iter().chain(&scene_instances).flat_map(|inst| {
+            let clip = self.clip * inst.model;
+            inst.mesh.prims.iter().map(move |(prim, local_id)| {
+                let tag = PrimTag {
+                    id: inst.first_id + local_id,
+                    attr: inst.attr,
+                    object: inst.object,
+                };
+                Self::project_input(&inst.model, &clip, prim, tag)
+            })
+        });
+
+        let culled: Vec<_> = direct
+            .chain(instanced)
             // (conservatively) cull the primitives that are
             // completely outside of the render region.
-            .filter(|p| !self.is_prim_culled(p))
-            .filter_map(|p| {
-                if let Primitive::Triangle { tri } = p.clone() {
+            .filter(|zp| !self.is_prim_culled(&zp.p))
+            .filter_map(|mut zp| {
+                if let Primitive::Triangle { tri } = zp.p.clone() {
                     let winding = tri.winding_2d();
-                    match winding {
+                    zp.p = match winding {
                         Winding::Clockwise => match self.cull_mode {
-                            CullMode::Keep => Some(Primitive::Triangle { tri: tri.reverse() }),
-                            CullMode::MarkCulled => Some(Primitive::Triangle { tri: tri.cull() }),
-                            CullMode::Discard => None,
+                            CullMode::Keep => {
+                                // The back of the triangle faces the eye.
+                                zp.normal = zp.normal.map(|n| -n);
+                                Primitive::Triangle { tri: tri.reverse() }
+                            }
+                            CullMode::MarkCulled => Primitive::Triangle { tri: tri.cull() },
+                            CullMode::Discard => return None,
                         },
-                        Winding::Degenerate => None,
-                        _ => Some(p),
-                    }
-                } else {
-                    Some(p)
+                        Winding::Degenerate => return None,
+                        _ => zp.p,
+                    };
                 }
+                Some(zp)
             })
             .collect();
 
-        let mut prim_heap: BinaryHeap<ZsortPrim> =
-            culled.iter().map(|p| p.clone().into()).collect();
+        let mut prim_heap: BinaryHeap<ZsortPrim> = culled.into_iter().collect();
 
         // Tentatively rendered primitives (that might be later rejected.
         let mut rendered_prims = vec![];
@@ -265,7 +503,7 @@ impl Renderer {
                     }
                     if self.debug {
                         render_partial(
-                            &*tri,
+                            tri,
                             &rendered_prims,
                             &prim_heap,
                             None,
@@ -294,7 +532,7 @@ impl Renderer {
 
                                 // try to split the triangle on the line
                                 if let SplitResult::Split(tris) =
-                                    split_triangle_by_segment(&tri, pa, pb)
+                                    split_triangle_by_segment(tri, pa, pb)
                                 {
                                     if self.debug {
                                         render_partial(
@@ -311,10 +549,9 @@ impl Renderer {
                                     let mut new_hs = x.presplit.clone();
                                     new_hs.insert((izp, i));
                                     for t in tris {
-                                        prim_heap.push(ZsortPrim::new(
-                                            Primitive::Triangle { tri: t },
-                                            &new_hs,
-                                        ));
+                                        prim_heap.push(
+                                            x.fragment(Primitive::Triangle { tri: t }, &new_hs),
+                                        );
                                     }
                                     continue 'prim_loop;
                                 }
@@ -322,7 +559,7 @@ impl Renderer {
 
                             // Check if the new triangle is contained
                             // within the current triangle.
-                            if triangle_in_triangle_2d(&tri, &test_tri) {
+                            if triangle_in_triangle_2d(tri, test_tri) {
                                 // For now, we assume that the new tri is behind.
                                 hidden = true;
                                 if self.debug {
@@ -331,7 +568,7 @@ impl Renderer {
                                         &rendered_prims,
                                         &prim_heap,
                                         None,
-                                        &*tri,
+                                        tri,
                                         &mut iter,
                                         added,
                                     );
@@ -368,13 +605,15 @@ impl Renderer {
             }
         }
 
-        rendered_prims.iter().collect()
+        let mut paths: RenderPaths = rendered_prims.iter().collect();
+        paths.attributes = attributes;
+        paths
     }
 }
 
 fn render_partial<'a, 'b, 'c>(
     next: impl Into<Option<&'b Tri>>,
-    rendered: &Vec<ZsortPrim>,
+    rendered: &[ZsortPrim],
     heap: &BinaryHeap<ZsortPrim>,
     split: impl Into<Option<(&'c Vec<Tri>, (DVec2, DVec2))>>,
     hidden: impl Into<Option<&'a Tri>>,
@@ -447,30 +686,24 @@ fn render_partial<'a, 'b, 'c>(
     };
 
     for (i, zprim) in rendered.iter().enumerate() {
-        match zprim.p {
-            Primitive::Triangle { ref tri } => {
-                g = add_prim(
-                    tri,
-                    if tri.is_hidden() {
-                        "hidden"
-                    } else if added && i == rendered.len() - 1 {
-                        "latest"
-                    } else {
-                        "rendered"
-                    },
-                    g,
-                );
-            }
-            _ => {}
+        if let Primitive::Triangle { ref tri } = zprim.p {
+            g = add_prim(
+                tri,
+                if tri.is_hidden() {
+                    "hidden"
+                } else if added && i == rendered.len() - 1 {
+                    "latest"
+                } else {
+                    "rendered"
+                },
+                g,
+            );
         }
     }
 
     for zprim in heap.iter() {
-        match zprim.p {
-            Primitive::Triangle { ref tri } => {
-                g = add_prim(tri, "ready", g);
-            }
-            _ => {}
+        if let Primitive::Triangle { ref tri } = zprim.p {
+            g = add_prim(tri, "ready", g);
         }
     }
 
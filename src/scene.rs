@@ -0,0 +1,157 @@
+//! Hierarchical scenes of meshes with nested transforms.
+//!
+//! A tree of `Node`s is added to the `Renderer` with
+//! `Renderer::add_node`, and flattened into mesh instances at render
+//! time. Whole subtrees are skipped when they are hidden, or when
+//! their bounding box lies entirely outside of the view frustum.
+
+use crate::common::*;
+use crate::convert::ToDMat4;
+use crate::mesh::Mesh;
+use crate::primitive::ObjectId;
+use crate::style::Attributes;
+use na::Matrix4;
+use std::sync::Arc;
+
+/// Axis-aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: DVec3,
+    pub max: DVec3,
+}
+
+impl Aabb {
+    pub fn new(min: DVec3, max: DVec3) -> Aabb {
+        Aabb { min, max }
+    }
+
+    /// Return the smallest box containing all of `points`, or `None`
+    /// if there are no points.
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a DVec3>) -> Option<Aabb> {
+        points.into_iter().fold(None, |b: Option<Aabb>, p| {
+            Some(match b {
+                Some(b) => Aabb::new(b.min.inf(p), b.max.sup(p)),
+                None => Aabb::new(*p, *p),
+            })
+        })
+    }
+
+    /// Return the smallest box containing both boxes.
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb::new(self.min.inf(&other.min), self.max.sup(&other.max))
+    }
+
+    /// The eight corners of the box.
+    pub fn corners(&self) -> [DVec3; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            vec3(a.x, a.y, a.z),
+            vec3(b.x, a.y, a.z),
+            vec3(a.x, b.y, a.z),
+            vec3(b.x, b.y, a.z),
+            vec3(a.x, a.y, b.z),
+            vec3(b.x, a.y, b.z),
+            vec3(a.x, b.y, b.z),
+            vec3(b.x, b.y, b.z),
+        ]
+    }
+
+    /// Return the bounding box of this box after transforming it by
+    /// the affine matrix `m`.
+    pub fn transformed(&self, m: &Matrix4<f64>) -> Aabb {
+        let corners: Vec<DVec3> = self
+            .corners()
+            .iter()
+            .map(|c| (m * c.push(1.0)).xyz())
+            .collect();
+        Aabb::from_points(&corners).unwrap()
+    }
+}
+
+/// A node in a scene hierarchy.
+///
+/// The `transform` of a node is relative to its parent. Attributes and
+/// object ids are inherited by the children of a node, unless they
+/// set their own.
+#[derive(Debug, Clone)]
+pub struct Node {
+    /// Transform from this node's space into its parent's.
+    pub transform: Matrix4<f64>,
+
+    pub meshes: Vec<Arc<Mesh>>,
+
+    pub children: Vec<Node>,
+
+    /// If false, the node and all of its children are skipped.
+    pub visible: bool,
+
+    /// Bounding box of the node's meshes and children, in the node's
+    /// space. If `None`, it is computed when the node is added to the
+    /// `Renderer`.
+    pub bounds: Option<Aabb>,
+
+    pub attributes: Option<Attributes>,
+
+    pub object: Option<ObjectId>,
+}
+
+impl Default for Node {
+    fn default() -> Node {
+        Node::new(&Matrix4::<f64>::identity())
+    }
+}
+
+impl Node {
+    pub fn new(transform: &impl ToDMat4) -> Node {
+        Node {
+            transform: transform.to_dmat4(),
+            meshes: vec![],
+            children: vec![],
+            visible: true,
+            bounds: None,
+            attributes: None,
+            object: None,
+        }
+    }
+
+    pub fn add_mesh(&mut self, mesh: &Arc<Mesh>) {
+        self.meshes.push(mesh.clone());
+    }
+
+    /// Add a child node, returning a reference to it.
+    pub fn add_child(&mut self, child: Node) -> &mut Node {
+        self.children.push(child);
+        self.children.last_mut().unwrap()
+    }
+
+    /// Number of primitive ids used by the whole subtree, including
+    /// hidden nodes.
+    pub fn num_ids(&self) -> usize {
+        self.meshes.iter().map(|m| m.num_ids()).sum::<usize>()
+            + self.children.iter().map(|c| c.num_ids()).sum::<usize>()
+    }
+
+    /// Compute the bounds of every node in the subtree that doesn't
+    /// have any, returning the bounds of this node.
+    pub fn fill_bounds(&mut self) -> Option<Aabb> {
+        let child_bounds: Vec<Aabb> = self
+            .children
+            .iter_mut()
+            .filter_map(|c| {
+                let transform = c.transform;
+                c.fill_bounds().map(|b| b.transformed(&transform))
+            })
+            .collect();
+        if self.bounds.is_none() {
+            self.bounds = self
+                .meshes
+                .iter()
+                .filter_map(|m| m.bounds())
+                .chain(child_bounds)
+                .fold(None, |acc: Option<Aabb>, b| {
+                    Some(acc.map_or(b, |a| a.union(&b)))
+                });
+        }
+        self.bounds
+    }
+}
@@ -0,0 +1,85 @@
+//! Flat shading of the visible faces.
+
+use crate::common::*;
+use crate::convert::IntoDVec3;
+use crate::face::RenderFace;
+use crate::style::{Attributes, Color};
+
+/// A directional light, infinitely far away.
+#[derive(Debug, Clone, Copy)]
+pub struct Light {
+    /// World-space unit vector pointing towards the light, or zero
+    /// for a light without a direction.
+    pub direction: DVec3,
+
+    /// Brightness of faces facing away from the light, in `[0, 1]`.
+    pub ambient: f64,
+}
+
+impl Light {
+    /// Create a light shining from `direction`, with no ambient
+    /// light. A zero `direction` gives a light without a direction,
+    /// which lights every surface fully.
+    pub fn new(direction: impl IntoDVec3) -> Light {
+        Light {
+            direction: direction
+                .into_dvec3()
+                .try_normalize(0.0)
+                .unwrap_or_else(DVec3::zeros),
+            ambient: 0.0,
+        }
+    }
+
+    /// Lambertian brightness, in `[0, 1]`, of a surface with the unit
+    /// normal `n`.
+    pub fn intensity(&self, n: &DVec3) -> f64 {
+        if self.direction == DVec3::zeros() {
+            return 1.0;
+        }
+        let diffuse = n.dot(&self.direction).max(0.0);
+        (self.ambient + (1.0 - self.ambient) * diffuse).clamp(0.0, 1.0)
+    }
+
+    /// Brightness of a visible face. Faces without a normal are lit
+    /// fully.
+    pub fn face_intensity(&self, face: &RenderFace) -> f64 {
+        face.normal().map_or(1.0, |n| self.intensity(&n))
+    }
+}
+
+/// Options for shading the visible faces in the SVG output.
+#[derive(Debug, Clone, Copy)]
+pub struct Shading {
+    pub light: Light,
+
+    /// Colour of the faces without a `fill` attribute.
+    pub base_color: Color,
+}
+
+impl Shading {
+    pub fn new(light: Light) -> Shading {
+        Shading {
+            light,
+            base_color: Color::WHITE,
+        }
+    }
+
+    /// Shaded fill colour of `face`, starting from `base`.
+    pub fn fill(&self, face: &RenderFace, base: Option<Color>) -> Color {
+        base.unwrap_or(self.base_color)
+            .scale(self.light.face_intensity(face))
+    }
+}
+
+/// Tone of `face` in `[0, 1]`, from black to white: the `tone`
+/// attribute of the face if set, or else its brightness under
+/// `light`.
+pub fn face_tone(
+    face: &RenderFace,
+    attr: Option<&Attributes>,
+    light: Option<&Light>,
+) -> Option<f64> {
+    attr.and_then(|a| a.tone)
+        .or_else(|| light.map(|l| l.face_intensity(face)))
+        .map(|t| t.clamp(0.0, 1.0))
+}
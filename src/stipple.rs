@@ -0,0 +1,146 @@
+//! Stippling of the visible faces: filling each face with dots whose
+//! density follows its tone.
+//!
+//! The dots of a face are Poisson-disc distributed, so that no two
+//! are closer than a minimum distance, which gives them a blue noise
+//! spectrum without visible patterns. The distance shrinks as the face
+//! gets darker.
+
+use crate::common::*;
+use crate::face::RenderFace;
+use crate::shade::{face_tone, Light};
+use crate::style::Attributes;
+use std::collections::HashMap;
+
+/// Options for `RenderPaths::add_stippling`.
+#[derive(Debug, Clone, Copy)]
+pub struct StippleOptions {
+    /// Minimum distance between the dots of a black face, in NDC
+    /// units.
+    pub min_spacing: f64,
+
+    /// Faces whose dots would be further apart than this are left
+    /// empty.
+    pub max_spacing: f64,
+
+    /// Number of candidate dots tried per accepted dot. Higher values
+    /// pack the dots more tightly.
+    pub attempts: usize,
+
+    /// Seed of the random number generator. The same seed always
+    /// gives the same dots.
+    pub seed: u64,
+
+    /// Light used for the tone of faces without a `tone` attribute.
+    /// Faces without either are not stippled.
+    pub light: Option<Light>,
+}
+
+impl StippleOptions {
+    pub fn new(min_spacing: f64) -> StippleOptions {
+        StippleOptions {
+            min_spacing,
+            max_spacing: min_spacing * 8.0,
+            attempts: 10,
+            seed: 0,
+            light: None,
+        }
+    }
+
+    /// Minimum distance between the dots for a face of tone `tone`,
+    /// or `None` if the face is too light to be stippled.
+    pub fn spacing(&self, tone: f64) -> Option<f64> {
+        let darkness = 1.0 - tone;
+        if darkness <= 0.0 {
+            return None;
+        }
+        // the density of the dots is proportional to the darkness
+        let spacing = self.min_spacing / darkness.sqrt();
+        if spacing > self.max_spacing {
+            None
+        } else {
+            Some(spacing)
+        }
+    }
+
+    /// Stipple dots of `face`, given its attributes.
+    pub fn stipple_face(&self, face: &RenderFace, attr: Option<&Attributes>) -> Vec<DVec2> {
+        let tone = face_tone(face, attr, self.light.as_ref());
+        let spacing = match tone.and_then(|t| self.spacing(t)) {
+            Some(s) => s,
+            None => return vec![],
+        };
+        // vary the dots between faces
+        let id = face.id().unwrap_or(0) as u64;
+        let seed = self.seed ^ id.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        poisson_disc(face, spacing, self.attempts, seed)
+    }
+}
+
+/// Dart throwing: try random points in the bounding box of the face,
+/// keeping those inside the face and at least `spacing` from every
+/// kept point.
+fn poisson_disc(face: &RenderFace, spacing: f64, attempts: usize, seed: u64) -> Vec<DVec2> {
+    let (mut lo, mut hi) = (
+        vec2(f64::INFINITY, f64::INFINITY),
+        vec2(f64::NEG_INFINITY, f64::NEG_INFINITY),
+    );
+    for p in face.loops().iter().flatten() {
+        lo = lo.inf(p);
+        hi = hi.sup(p);
+    }
+    if lo.x > hi.x || spacing <= 0.0 {
+        return vec![];
+    }
+    let size = hi - lo;
+
+    // Each grid cell holds at most one point, so only the 5x5 block
+    // of cells around a candidate needs to be checked.
+    let cell = spacing / 2.0f64.sqrt();
+    let cell_of = |p: DVec2| {
+        (
+            ((p.x - lo.x) / cell).floor() as i64,
+            ((p.y - lo.y) / cell).floor() as i64,
+        )
+    };
+    let mut grid: HashMap<(i64, i64), DVec2> = HashMap::new();
+
+    let mut rng = SplitMix64(seed);
+    let darts = (face.area() / (spacing * spacing)).ceil() as usize * attempts;
+    let mut points = vec![];
+    for _ in 0..darts {
+        let p = lo + vec2(rng.next_f64() * size.x, rng.next_f64() * size.y);
+        if !face.contains(p) {
+            continue;
+        }
+        let (cx, cy) = cell_of(p);
+        let near = (cx - 2..=cx + 2)
+            .flat_map(|x| (cy - 2..=cy + 2).map(move |y| (x, y)))
+            .filter_map(|c| grid.get(&c))
+            .any(|q| (q - p).norm() < spacing);
+        if !near {
+            grid.insert((cx, cy), p);
+            points.push(p);
+        }
+    }
+    points
+}
+
+/// A small, fast, seedable generator, so that stippling is
+/// reproducible without depending on an external crate.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform sample in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
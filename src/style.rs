@@ -0,0 +1,195 @@
+//! Styling information that can be attached to primitives and
+//! carried through to the rendered output.
+
+use crate::primitive::EdgeType;
+use std::fmt;
+
+/// An 8-bit sRGB colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b }
+    }
+
+    /// Parse a colour of the form `#rrggbb` or `#rgb`. The leading
+    /// `#` is optional.
+    pub fn from_hex(s: &str) -> Option<Color> {
+        let s = s.strip_prefix('#').unwrap_or(s);
+        if !s.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize, n: usize| u8::from_str_radix(&s[i..i + n], 16).ok();
+        match s.len() {
+            6 => Some(Color::rgb(channel(0, 2)?, channel(2, 2)?, channel(4, 2)?)),
+            3 => Some(Color::rgb(
+                channel(0, 1)? * 17,
+                channel(1, 1)? * 17,
+                channel(2, 1)? * 17,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Scale the brightness of the colour by `k`, clamped to `[0, 1]`.
+    pub fn scale(self, k: f64) -> Color {
+        let k = k.clamp(0.0, 1.0);
+        let c = |x: u8| (x as f64 * k).round() as u8;
+        Color::rgb(c(self.r), c(self.g), c(self.b))
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// Optional styling for a primitive.
+///
+/// Attributes follow a primitive through any splitting done by the
+/// `Renderer`, and end up on every `RenderLine` generated from it.
+/// Any field left as `None` falls back to the style of the line's
+/// `EdgeType`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Attributes {
+    /// Stroke colour of the edges.
+    pub stroke: Option<Color>,
+
+    /// Fill colour of the visible faces.
+    pub fill: Option<Color>,
+
+    /// Stroke width of the edges, in output units.
+    pub stroke_width: Option<f64>,
+
+    /// Name of the layer the primitive belongs to.
+    pub layer: Option<String>,
+
+    /// Additional CSS class(es) for the edges.
+    pub class: Option<String>,
+
+    /// Tone of the faces when hatched, from 0 (black) to 1 (white).
+    pub tone: Option<f64>,
+}
+
+/// Shape of the ends of a stroke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
+
+impl fmt::Display for LineCap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "square",
+        })
+    }
+}
+
+/// Shape of the corners of a stroke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+impl fmt::Display for LineJoin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LineJoin::Miter => "miter",
+            LineJoin::Round => "round",
+            LineJoin::Bevel => "bevel",
+        })
+    }
+}
+
+/// Stroke style of the lines of one `EdgeType`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineStyle {
+    pub stroke: Color,
+
+    /// Stroke width, in output units.
+    pub width: f64,
+
+    /// Alternating dash and gap lengths, in output units. Solid if
+    /// empty.
+    pub dash: Vec<f64>,
+
+    pub cap: LineCap,
+    pub join: LineJoin,
+}
+
+impl LineStyle {
+    /// A solid line.
+    pub fn new(stroke: Color, width: f64) -> LineStyle {
+        LineStyle {
+            stroke,
+            width,
+            dash: vec![],
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+        }
+    }
+
+    /// Return this style with the dash pattern `dash`.
+    pub fn dashed(self, dash: &[f64]) -> LineStyle {
+        LineStyle {
+            dash: dash.to_vec(),
+            ..self
+        }
+    }
+
+    /// Default style of the lines of type `edge`.
+    pub fn for_edge(edge: EdgeType) -> LineStyle {
+        let (dark, blue, grey, green, red) = (
+            Color::rgb(0x44, 0x44, 0x44),
+            Color::rgb(0x22, 0x22, 0xcc),
+            Color::rgb(0xaa, 0xaa, 0xaa),
+            Color::rgb(0x22, 0xcc, 0x22),
+            Color::rgb(0xcc, 0x22, 0x22),
+        );
+        match edge {
+            EdgeType::Visible => LineStyle::new(dark, 1.0),
+            EdgeType::Hidden => LineStyle::new(blue, 0.5).dashed(&[2.0, 1.0]),
+            EdgeType::Invisible => LineStyle::new(grey, 0.25).dashed(&[0.25, 0.25]),
+            EdgeType::Split => LineStyle::new(green, 0.25).dashed(&[0.5, 0.5]),
+            EdgeType::Culled => LineStyle::new(red, 0.25).dashed(&[1.0, 1.0]),
+            EdgeType::Hatch => LineStyle::new(dark, 0.5),
+        }
+    }
+
+    /// SVG presentation attributes (and CSS properties) of the style.
+    pub fn properties(&self) -> Vec<(&'static str, String)> {
+        let mut props = vec![
+            ("fill", "none".to_string()),
+            ("stroke", self.stroke.to_string()),
+            ("stroke-width", self.width.to_string()),
+        ];
+        if !self.dash.is_empty() {
+            let dash: Vec<String> = self.dash.iter().map(|d| d.to_string()).collect();
+            props.push(("stroke-dasharray", dash.join(" ")));
+        }
+        props.push(("stroke-linecap", self.cap.to_string()));
+        props.push(("stroke-linejoin", self.join.to_string()));
+        props
+    }
+}
+
+/// Format a CSS rule from a selector and properties.
+pub(crate) fn css_rule(selector: &str, props: &[(&str, String)]) -> String {
+    let decls: Vec<String> = props.iter().map(|(k, v)| format!("{}: {};", k, v)).collect();
+    format!("{} {{ {} }}\n", selector, decls.join(" "))
+}
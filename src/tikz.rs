@@ -0,0 +1,171 @@
+//! TikZ output, for including drawings in LaTeX documents.
+
+use crate::common::*;
+use crate::page::Unit;
+use crate::pdf::{num, PT_PER_INCH};
+use crate::render_paths::{Marker, RenderPaths, SvgOptions};
+use crate::style::{Color, LineCap, LineStyle};
+use std::io::{self, Write};
+
+/// Centimetres per inch, the TikZ coordinate unit.
+const CM_PER_INCH: f64 = 2.54;
+
+fn color(c: Color) -> String {
+    format!("{{rgb,255:red,{};green,{};blue,{}}}", c.r, c.g, c.b)
+}
+
+/// Name of a TikZ style, with the characters that pgfkeys would read
+/// as syntax (separators, values, groups and paths) replaced by `-`.
+fn style_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if ",={}[]/\\%#".contains(c) { '-' } else { c })
+        .collect()
+}
+
+/// TikZ options of the style. Lengths are converted from page units
+/// to PostScript points (`bp`) by `scale`.
+fn style_options(style: &LineStyle, scale: f64) -> String {
+    let mut opts = vec![
+        format!("draw={}", color(style.stroke)),
+        format!("line width={}bp", num(style.width * scale)),
+    ];
+    if !style.dash.is_empty() {
+        // An odd number of lengths is repeated, as in SVG and PDF.
+        let mut lengths = style.dash.clone();
+        if lengths.len() % 2 == 1 {
+            lengths.extend_from_slice(&style.dash);
+        }
+        let dash: Vec<String> = lengths
+            .chunks(2)
+            .map(|d| format!("on {}bp off {}bp", num(d[0] * scale), num(d[1] * scale)))
+            .collect();
+        opts.push(format!("dash pattern={}", dash.join(" ")));
+    }
+    opts.push(format!(
+        "line cap={}",
+        match style.cap {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "rect",
+        }
+    ));
+    opts.push(format!("line join={}", style.join));
+    opts.join(", ")
+}
+
+impl RenderPaths {
+    /// Write the faces, lines and points as a `tikzpicture`, with one
+    /// TikZ style per edge type, named as in `SvgOptions::edge_name`,
+    /// and one for the point markers, named by their class. Characters
+    /// with a meaning to pgfkeys (`,={}[]/\%#`) are replaced by `-` in
+    /// the style names. Coordinates are in centimetres from the
+    /// bottom left of the page.
+    pub fn write_tikz<W: Write>(&self, w: &mut W, options: &SvgOptions) -> io::Result<()> {
+        let page = &options.page;
+        let scale = page.unit.convert(1.0, Unit::In) * PT_PER_INCH;
+        let to_cm = |p: &DVec2| page.to_y_up(*p, Unit::In) * CM_PER_INCH;
+        let cm = |p: &DVec2| {
+            let q = to_cm(p);
+            format!("({},{})", num(q.x), num(q.y))
+        };
+        let markers = &options.markers;
+        let marker_style = style_name(&markers.class);
+
+        let strokes = self.strokes_in_paint_order(options);
+        let edges: Vec<EdgeType> = options
+            .edge_types_ordered()
+            .into_iter()
+            .filter(|e| strokes.iter().any(|s| s.edge == *e))
+            .collect();
+
+        writeln!(w, "\\begin{{tikzpicture}}[")?;
+        for e in &edges {
+            let style = style_options(&options.edge_style(*e), scale);
+            let name = style_name(&options.edge_name(*e));
+            writeln!(w, "  {}/.style={{{}}},", name, style)?;
+        }
+        if !self.points.is_empty() {
+            let style = match markers.shape {
+                Marker::Cross => format!(
+                    "draw={}, line width={}bp, line cap=butt",
+                    color(markers.color),
+                    num(markers.stroke_width * scale)
+                ),
+                _ => format!("fill={}", color(markers.color)),
+            };
+            writeln!(w, "  {}/.style={{{}}},", marker_style, style)?;
+        }
+        writeln!(w, "]")?;
+
+        if options.faces {
+            for face in &self.faces {
+                let fill = self.face_color(face, options).unwrap_or(options.face_fill);
+                let loops: Vec<String> = face
+                    .loops
+                    .iter()
+                    .filter(|l| !l.is_empty())
+                    .map(|l| {
+                        let points: Vec<String> = l.iter().map(&cm).collect();
+                        format!("{} -- cycle", points.join(" -- "))
+                    })
+                    .collect();
+                writeln!(
+                    w,
+                    "\\fill[fill={}, even odd rule] {};",
+                    color(fill),
+                    loops.join(" ")
+                )?;
+            }
+        }
+
+        for s in strokes.iter().filter(|s| s.points.len() >= 2) {
+            let mut opts = vec![style_name(&options.edge_name(s.edge))];
+            if let Some(attr) = self.polyline_attributes(s) {
+                if let Some(stroke) = attr.stroke {
+                    opts.push(format!("draw={}", color(stroke)));
+                }
+                if let Some(width) = attr.stroke_width {
+                    opts.push(format!("line width={}bp", num(width * scale)));
+                }
+            }
+            let mut path: Vec<String> = s.points.iter().map(&cm).collect();
+            if s.is_closed() {
+                // TikZ closes the path itself, joining the ends.
+                *path.last_mut().unwrap() = "cycle".to_string();
+            }
+            writeln!(w, "\\draw[{}] {};", opts.join(", "), path.join(" -- "))?;
+        }
+
+        for point in &self.points {
+            let c = to_cm(&point.point());
+            let r = page.unit.convert(markers.point_size(point), Unit::In) * CM_PER_INCH;
+            let at = |x: f64, y: f64| format!("({},{})", num(c.x + x), num(c.y + y));
+            match markers.shape {
+                Marker::Circle => writeln!(
+                    w,
+                    "\\fill[{}] {} circle [radius={}];",
+                    marker_style,
+                    at(0.0, 0.0),
+                    num(r)
+                )?,
+                Marker::Square => writeln!(
+                    w,
+                    "\\fill[{}] {} rectangle {};",
+                    marker_style,
+                    at(-r, -r),
+                    at(r, r)
+                )?,
+                Marker::Cross => writeln!(
+                    w,
+                    "\\draw[{}] {} -- {} {} -- {};",
+                    marker_style,
+                    at(-r, -r),
+                    at(r, r),
+                    at(-r, r),
+                    at(r, -r)
+                )?,
+            }
+        }
+        writeln!(w, "\\end{{tikzpicture}}")
+    }
+}
@@ -0,0 +1,251 @@
+//! Ordering of the strokes to reduce the pen-up travel of a plotter.
+//!
+//! The strokes are first ordered greedily, always moving to the
+//! nearest end of a remaining stroke, and the order is then improved
+//! with 2-opt moves between nearby positions in the order. Strokes
+//! may be drawn in either direction.
+
+use crate::common::*;
+use crate::polyline::RenderPolyline;
+
+/// Where the pen starts: the top left corner of the NDC square.
+pub const PEN_HOME: DVec2 = DVec2::new(-1.0, 1.0);
+
+/// Maximum number of 2-opt passes over the strokes.
+const MAX_PASSES: usize = 16;
+
+/// Longest run of strokes a 2-opt move reverses, which keeps each
+/// pass linear in the number of strokes.
+const MAX_RUN: usize = 64;
+
+/// Pen-down and pen-up distances of a plot, in NDC units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TravelStats {
+    /// Total length of the strokes.
+    pub draw: f64,
+
+    /// Total distance moved between strokes, starting from
+    /// `PEN_HOME`.
+    pub travel: f64,
+}
+
+impl TravelStats {
+    pub fn of(strokes: &[RenderPolyline]) -> TravelStats {
+        let mut pen = PEN_HOME;
+        let mut stats = TravelStats {
+            draw: 0.0,
+            travel: 0.0,
+        };
+        for s in strokes {
+            if let (Some(first), Some(last)) = (s.points.first(), s.points.last()) {
+                stats.travel += (first - pen).norm();
+                stats.draw += s.length();
+                pen = *last;
+            }
+        }
+        stats
+    }
+}
+
+/// Travel statistics before and after reordering.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TravelReport {
+    pub before: TravelStats,
+    pub after: TravelStats,
+}
+
+impl TravelReport {
+    /// Pen-up distance saved by the reordering.
+    pub fn saved(&self) -> f64 {
+        self.before.travel - self.after.travel
+    }
+}
+
+/// Reorder, and reverse where it helps, `strokes` to reduce the
+/// pen-up travel between them.
+pub fn order_strokes(strokes: Vec<RenderPolyline>) -> Vec<RenderPolyline> {
+    let (mut empty, strokes): (Vec<_>, Vec<_>) =
+        strokes.into_iter().partition(|s| s.points.is_empty());
+    let mut ordered = greedy(strokes);
+    two_opt(&mut ordered);
+    ordered.append(&mut empty);
+    ordered
+}
+
+fn start(s: &RenderPolyline) -> DVec2 {
+    s.points[0]
+}
+
+fn end(s: &RenderPolyline) -> DVec2 {
+    s.points[s.points.len() - 1]
+}
+
+/// The end points of the remaining strokes, hashed into a grid of
+/// about one cell per stroke for nearest neighbour queries.
+struct EndGrid {
+    min: DVec2,
+    max: DVec2,
+    cell: f64,
+    size: (i64, i64),
+    /// Stroke index and whether the point is its end, per cell.
+    cells: Vec<Vec<(usize, bool)>>,
+    built: usize,
+}
+
+impl EndGrid {
+    fn new(strokes: &[Option<RenderPolyline>]) -> EndGrid {
+        let live: Vec<(usize, &RenderPolyline)> = strokes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, s)| s.as_ref().map(|s| (i, s)))
+            .collect();
+        let mut min = DVec2::repeat(f64::INFINITY);
+        let mut max = DVec2::repeat(f64::NEG_INFINITY);
+        for (_, s) in &live {
+            for p in [start(s), end(s)].iter() {
+                min = glm::min2(&min, p);
+                max = glm::max2(&max, p);
+            }
+        }
+        let extent = (max - min).max().max(f64::EPSILON);
+        let cell = extent / (live.len() as f64).sqrt().ceil().max(1.0);
+        let size = (
+            ((max.x - min.x) / cell) as i64 + 1,
+            ((max.y - min.y) / cell) as i64 + 1,
+        );
+        let mut grid = EndGrid {
+            min,
+            max,
+            cell,
+            size,
+            cells: vec![vec![]; (size.0 * size.1) as usize],
+            built: live.len(),
+        };
+        for (i, s) in live {
+            grid.cell_mut(start(s)).push((i, false));
+            grid.cell_mut(end(s)).push((i, true));
+        }
+        grid
+    }
+
+    fn coords(&self, p: DVec2) -> (i64, i64) {
+        let q = (p - self.min) / self.cell;
+        (
+            (q.x as i64).clamp(0, self.size.0 - 1),
+            (q.y as i64).clamp(0, self.size.1 - 1),
+        )
+    }
+
+    fn cell_mut(&mut self, p: DVec2) -> &mut Vec<(usize, bool)> {
+        let (x, y) = self.coords(p);
+        &mut self.cells[(y * self.size.0 + x) as usize]
+    }
+
+    fn remove(&mut self, i: usize, s: &RenderPolyline) {
+        for p in [start(s), end(s)].iter() {
+            self.cell_mut(*p).retain(|(j, _)| *j != i);
+        }
+    }
+
+    /// The stroke with the end point nearest to `pen`, and whether
+    /// that is its end.
+    fn nearest(&self, pen: DVec2, strokes: &[Option<RenderPolyline>]) -> Option<(usize, bool)> {
+        // The search starts from the point of the grid nearest to the
+        // pen, q. Along each axis, the offset from the pen to a point
+        // of the grid is that from the pen to q plus that from q, so
+        // the cells beyond ring r are at least hypot(|pen - q|, r *
+        // cell) from the pen.
+        let q = glm::clamp_vec(&pen, &self.min, &self.max);
+        let outside = (pen - q).norm();
+        let (cx, cy) = self.coords(q);
+        let mut best: Option<(f64, usize, bool)> = None;
+        let visit = |best: &mut Option<(f64, usize, bool)>, x: i64, y: i64| {
+            if x < 0 || y < 0 || x >= self.size.0 || y >= self.size.1 {
+                return;
+            }
+            for &(i, is_end) in &self.cells[(y * self.size.0 + x) as usize] {
+                let s = strokes[i].as_ref().unwrap();
+                let d = (if is_end { end(s) } else { start(s) } - pen).norm();
+                if !matches!(*best, Some(b) if (b.0, b.1) <= (d, i)) {
+                    *best = Some((d, i, is_end));
+                }
+            }
+        };
+        for r in 0..=self.size.0.max(self.size.1) {
+            // the cells at a distance of exactly r from (cx, cy)
+            if r == 0 {
+                visit(&mut best, cx, cy);
+            }
+            for d in -r..r {
+                visit(&mut best, cx + d, cy - r);
+                visit(&mut best, cx + r, cy + d);
+                visit(&mut best, cx - d, cy + r);
+                visit(&mut best, cx - r, cy - d);
+            }
+            if let Some((d, ..)) = best {
+                if d <= outside.hypot(r as f64 * self.cell) {
+                    break;
+                }
+            }
+        }
+        best.map(|(_, i, is_end)| (i, is_end))
+    }
+}
+
+/// Nearest neighbour ordering, starting from `PEN_HOME`.
+fn greedy(strokes: Vec<RenderPolyline>) -> Vec<RenderPolyline> {
+    let mut ordered = Vec::with_capacity(strokes.len());
+    let mut remaining: Vec<Option<RenderPolyline>> = strokes.into_iter().map(Some).collect();
+    let mut grid = EndGrid::new(&remaining);
+    let mut live = remaining.len();
+    let mut pen = PEN_HOME;
+    while let Some((i, reversed)) = grid.nearest(pen, &remaining) {
+        let mut s = remaining[i].take().unwrap();
+        grid.remove(i, &s);
+        live -= 1;
+        if reversed {
+            s.reverse();
+        }
+        pen = end(&s);
+        ordered.push(s);
+
+        // Keep the cells dense as the strokes are used up, so that
+        // the searches stay short.
+        if live > 0 && live * 4 < grid.built {
+            grid = EndGrid::new(&remaining);
+        }
+    }
+    ordered
+}
+
+/// Improve the order with 2-opt moves: drawing a run of up to
+/// `MAX_RUN` strokes backwards (in reverse order, and each reversed)
+/// wherever that shortens the travel into and out of the run.
+fn two_opt(strokes: &mut [RenderPolyline]) {
+    let n = strokes.len();
+    for _ in 0..MAX_PASSES {
+        let mut improved = false;
+        for i in 0..n {
+            let before = if i == 0 {
+                PEN_HOME
+            } else {
+                end(&strokes[i - 1])
+            };
+            for j in i + 1..n.min(i + MAX_RUN) {
+                let after = strokes.get(j + 1).map(start);
+                let old = (start(&strokes[i]) - before).norm()
+                    + after.map_or(0.0, |a| (a - end(&strokes[j])).norm());
+                let new = (end(&strokes[j]) - before).norm()
+                    + after.map_or(0.0, |a| (a - start(&strokes[i])).norm());
+                if new < old - EPS {
+                    strokes[i..=j].reverse();
+                    strokes[i..=j].iter_mut().for_each(|s| s.reverse());
+                    improved = true;
+                }
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use glm::{look_at, perspective, vec3};
+use nalgebra_glm as glm;
+use vectorfoil::{Attributes, Color, Renderer, SvgOptions};
+
+fn renderer() -> Renderer {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = perspective(1.0, std::f64::consts::FRAC_PI_2, 0.1, 10.0);
+    Renderer::new(&(proj * view))
+}
+
+fn options(by_layer: bool) -> SvgOptions {
+    SvgOptions {
+        by_layer,
+        ..SvgOptions::new(100.0, 100.0)
+    }
+}
+
+#[test]
+fn color_hex() {
+    assert_eq!(Color::from_hex("#ff8000"), Some(Color::rgb(255, 128, 0)));
+    assert_eq!(Color::from_hex("f80"), Some(Color::rgb(255, 136, 0)));
+    assert_eq!(Color::from_hex("#ff80"), None);
+    assert_eq!(Color::rgb(255, 128, 0).to_string(), "#ff8000");
+}
+
+#[test]
+fn attributes_survive_splitting() {
+    let mut r = renderer();
+    r.set_attributes(Attributes {
+        stroke: Some(Color::rgb(255, 0, 0)),
+        layer: Some("front".to_string()),
+        ..Default::default()
+    });
+    r.add_triangle(
+        vec3(-1.0, -1.0, 1.0),
+        vec3(0.5, 0.0, 1.0),
+        vec3(-1.0, 1.0, 1.0),
+    );
+    r.set_attributes(Attributes {
+        stroke: Some(Color::rgb(0, 0, 255)),
+        class: Some("part".to_string()),
+        ..Default::default()
+    });
+    r.add_triangle(
+        vec3(-0.5, 0.0, -1.0),
+        vec3(1.0, -1.0, -1.0),
+        vec3(1.0, 1.0, -1.0),
+    );
+    r.set_attributes(None);
+    r.add_line(vec3(-1.0, -1.0, 0.0), vec3(1.0, 1.0, 0.0));
+
+    let rp = r.render();
+    assert_eq!(rp.attributes.len(), 2);
+
+    // the back triangle is split, but every fragment keeps its style
+    let red = rp
+        .lines
+        .iter()
+        .filter(|l| rp.line_attributes(l).and_then(|a| a.stroke) == Some(Color::rgb(255, 0, 0)))
+        .count();
+    let blue = rp
+        .lines
+        .iter()
+        .filter(|l| rp.line_attributes(l).and_then(|a| a.stroke) == Some(Color::rgb(0, 0, 255)))
+        .count();
+    let unstyled = rp
+        .lines
+        .iter()
+        .filter(|l| rp.line_attributes(l).is_none())
+        .count();
+    assert_eq!(red, 3);
+    assert!(blue > 3);
+    assert_eq!(unstyled, 1);
+
+    let svg = rp.as_standalone_svg(&options(false)).to_string();
+    assert!(svg.contains("class=\"visible part\""));
+
+    // The stylesheet sets the stroke of each edge type, which would
+    // override a presentation attribute, so the per-primitive stroke
+    // has to be in the `style` attribute to be drawn.
+    assert!(svg.contains(".visible { fill: none; stroke: #444444;"));
+    assert!(svg.contains("style=\"stroke:#ff0000\""));
+    assert!(!svg.contains("stroke=\"#ff0000\""));
+
+    let svg = rp.as_standalone_svg(&options(true)).to_string();
+    assert!(svg.contains("data-layer=\"front\""));
+}
+
+#[test]
+fn attribute_style_overrides_edge_style() {
+    let mut r = renderer();
+    r.set_attributes(Attributes {
+        stroke: Some(Color::rgb(0, 0, 255)),
+        stroke_width: Some(2.0),
+        ..Default::default()
+    });
+    r.add_line(vec3(-1.0, -1.0, 0.0), vec3(1.0, 1.0, 0.0));
+    let rp = r.render();
+
+    // A `style` attribute wins over both the stylesheet and the
+    // inline presentation attributes of the edge type.
+    for inline_styles in [false, true].iter().copied() {
+        let opt = SvgOptions {
+            inline_styles,
+            ..options(false)
+        };
+        let svg = rp.as_standalone_svg(&opt).to_string();
+        assert!(svg.contains("style=\"stroke:#0000ff;stroke-width:2\""));
+    }
+}
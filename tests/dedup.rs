@@ -0,0 +1,160 @@
+use assert_approx_eq::assert_approx_eq;
+use glm::{look_at, ortho, vec2, vec3, vec4};
+use nalgebra_glm as glm;
+use vectorfoil::dedup::{dedup_lines, DEFAULT_PRECEDENCE};
+use vectorfoil::{EdgeType, RenderLine, Renderer};
+
+#[test]
+fn overlap_precedence() {
+    let l = |a: f64, b: f64, e| RenderLine::new(vec2(a, 0.0), vec2(b, 0.0), e);
+    let lines = [
+        l(0.0, 1.0, EdgeType::Invisible),
+        // reversed, and overlapping the middle
+        l(0.75, 0.25, EdgeType::Visible),
+        l(0.5, 2.0, EdgeType::Split),
+        // an exact copy
+        l(0.0, 1.0, EdgeType::Invisible),
+        // elsewhere
+        RenderLine::new(vec2(0.0, 1.0), vec2(1.0, 1.0), EdgeType::Split),
+    ];
+    let out = dedup_lines(&lines, 1e-6, &DEFAULT_PRECEDENCE);
+    let on_axis: Vec<&RenderLine> = out.iter().filter(|l| l.points()[0].y == 0.0).collect();
+
+    // invisible [0, 0.25], visible [0.25, 0.75], split [0.75, 2]
+    assert_eq!(on_axis.len(), 3);
+    let length = |e| -> f64 {
+        on_axis
+            .iter()
+            .filter(|l| l.edge() == e)
+            .map(|l| l.length())
+            .sum()
+    };
+    assert_approx_eq!(length(EdgeType::Visible), 0.5);
+    assert_approx_eq!(length(EdgeType::Invisible), 0.25);
+    assert_approx_eq!(length(EdgeType::Split), 1.25);
+    let visible = on_axis.iter().find(|l| l.edge() == EdgeType::Visible).unwrap();
+    assert!(visible.points()[0].x > visible.points()[1].x);
+    assert_eq!(out.len(), 4);
+
+    // split lines win if ranked first
+    let out = dedup_lines(&lines, 1e-6, &[EdgeType::Split, EdgeType::Visible]);
+    let split: f64 = out
+        .iter()
+        .filter(|l| l.edge() == EdgeType::Split)
+        .map(|l| l.length())
+        .sum();
+    assert_approx_eq!(split, 2.5);
+}
+
+#[test]
+fn shared_edges_drawn_once() {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = ortho(-2.0, 2.0, -2.0, 2.0, 0.1, 10.0);
+    let mut r = Renderer::new(&(proj * view));
+    // two triangles sharing the diagonal
+    r.add_triangle(
+        vec3(-1.0, -1.0, 0.0),
+        vec3(1.0, -1.0, 0.0),
+        vec3(1.0, 1.0, 0.0),
+    );
+    r.add_triangle(
+        vec3(-1.0, -1.0, 0.0),
+        vec3(1.0, 1.0, 0.0),
+        vec3(-1.0, 1.0, 0.0),
+    );
+    let mut rp = r.render();
+    assert_eq!(rp.lines.len(), 6);
+    rp.dedup_lines(1e-6, &DEFAULT_PRECEDENCE);
+    assert_eq!(rp.lines.len(), 5);
+    let total: f64 = rp.lines.iter().map(|l| l.length()).sum();
+    assert_approx_eq!(total, 4.0 * 1.0 + 2.0f64.sqrt());
+}
+
+#[test]
+fn perspective_correct_cuts() {
+    let lines = [
+        RenderLine::from_ndc(
+            vec4(0.0, 0.0, 0.0, 1.0),
+            vec4(1.0, 0.0, 0.5, 3.0),
+            EdgeType::Invisible,
+        ),
+        RenderLine::new(vec2(0.5, 0.0), vec2(2.0, 0.0), EdgeType::Visible),
+    ];
+    let out = dedup_lines(&lines, 1e-6, &DEFAULT_PRECEDENCE);
+    let cut = out.iter().find(|l| l.edge() == EdgeType::Invisible).unwrap();
+    assert_approx_eq!(cut.points()[1].x, 0.5);
+    assert_approx_eq!(cut.depth()[1], 0.25);
+    // 1/w is linear in screen space, not w
+    assert_approx_eq!(cut.w()[1], 1.5);
+}
+
+#[test]
+fn opposite_directions_near_horizontal() {
+    // Directions just either side of the wrap-around at pi, and lines
+    // that are only nearly collinear.
+    let lines = [
+        RenderLine::new(vec2(0.0, 0.3), vec2(1.0, 0.3 + 1e-9), EdgeType::Invisible),
+        RenderLine::new(vec2(0.75, 0.3), vec2(0.25, 0.3 + 1e-9), EdgeType::Visible),
+        RenderLine::new(vec2(0.0, 0.3 + 1e-3), vec2(1.0, 0.3 + 1e-3), EdgeType::Split),
+    ];
+    let out = dedup_lines(&lines, 1e-6, &DEFAULT_PRECEDENCE);
+    assert_eq!(out.iter().filter(|l| l.edge() == EdgeType::Invisible).count(), 2);
+    assert_eq!(out.iter().filter(|l| l.edge() == EdgeType::Visible).count(), 1);
+    assert_eq!(out.iter().filter(|l| l.edge() == EdgeType::Split).count(), 1);
+}
+
+#[test]
+fn many_lines_match_brute_force() {
+    // Segments of a few long lines, cut at pseudo-random places, and
+    // given as separate copies with different edge types.
+    let mut lines = vec![];
+    let mut seed = 12345u64;
+    let mut next = || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+        (seed >> 11) as f64 / (1u64 << 53) as f64
+    };
+    for k in 0..20 {
+        let angle = k as f64 * 0.3;
+        let (d, o) = (vec2(angle.cos(), angle.sin()), vec2(-angle.sin(), angle.cos()) * 0.1);
+        for e in [EdgeType::Visible, EdgeType::Invisible].iter().copied() {
+            for _ in 0..20 {
+                let (a, b) = (next() * 2.0 - 1.0, next() * 2.0 - 1.0);
+                lines.push(RenderLine::new(o * k as f64 + d * a, o * k as f64 + d * b, e));
+            }
+        }
+    }
+    let out = dedup_lines(&lines, 1e-9, &DEFAULT_PRECEDENCE);
+    // every line of the same infinite line is covered once: the
+    // total length is that of the union of the intervals
+    for k in 0..20 {
+        let angle = k as f64 * 0.3;
+        let o = vec2(-angle.sin(), angle.cos()) * 0.1 * k as f64;
+        let d = vec2(angle.cos(), angle.sin());
+        let on = |l: &RenderLine| {
+            l.points()
+                .iter()
+                .all(|p| ((p - o).x * d.y - (p - o).y * d.x).abs() < 1e-7)
+        };
+        let mut iv: Vec<(f64, f64)> = lines
+            .iter()
+            .filter(|l| on(l))
+            .map(|l| {
+                let (a, b) = ((l.points()[0] - o).dot(&d), (l.points()[1] - o).dot(&d));
+                (a.min(b), a.max(b))
+            })
+            .collect();
+        iv.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let mut union = 0.0;
+        let mut end = f64::NEG_INFINITY;
+        for (a, b) in iv {
+            union += (b - end.max(a)).max(0.0);
+            end = end.max(b);
+        }
+        let total: f64 = out.iter().filter(|l| on(l)).map(|l| l.length()).sum();
+        assert_approx_eq!(total, union, 1e-6);
+    }
+}
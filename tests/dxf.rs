@@ -0,0 +1,81 @@
+use glm::vec2;
+use nalgebra_glm as glm;
+use vectorfoil::{DxfOptions, EdgeType, Page, RenderLine, RenderPaths, RenderPoint, Unit};
+
+fn dxf(rp: &RenderPaths, options: &DxfOptions) -> Vec<String> {
+    let mut out = vec![];
+    rp.write_dxf(&mut out, options).unwrap();
+    String::from_utf8(out).unwrap().lines().map(str::to_string).collect()
+}
+
+/// Values of group `code` following each occurrence of `entity`.
+fn values(out: &[String], entity: &str, code: &str) -> Vec<String> {
+    let mut found = vec![];
+    let mut inside = false;
+    for pair in out.chunks(2) {
+        if pair[0] == "0" {
+            inside = pair[1] == entity;
+        } else if inside && pair[0] == code {
+            found.push(pair[1].clone());
+        }
+    }
+    found
+}
+
+#[test]
+fn lines_on_edge_type_layers() {
+    let mut rp = RenderPaths::default();
+    rp.lines
+        .push(RenderLine::new(vec2(-1.0, -1.0), vec2(1.0, 1.0), EdgeType::Visible));
+    rp.lines
+        .push(RenderLine::new(vec2(-1.0, 1.0), vec2(0.0, 0.0), EdgeType::Hidden));
+    let out = dxf(&rp, &DxfOptions::new(Page::new(100.0, 50.0, Unit::Mm)));
+
+    assert_eq!(out.len() % 2, 0);
+    assert_eq!(out[out.len() - 1], "EOF");
+    assert!(out.windows(4).any(|w| w == ["9", "$ACADVER", "1", "AC1009"]));
+    assert_eq!(values(&out, "LAYER", "2"), ["0", "VISIBLE", "HIDDEN"]);
+    assert_eq!(values(&out, "LAYER", "6"), ["CONTINUOUS", "CONTINUOUS", "DASHED"]);
+    assert_eq!(values(&out, "LINE", "8"), ["VISIBLE", "HIDDEN"]);
+    assert_eq!(values(&out, "LINE", "11"), ["100", "50"]);
+    assert_eq!(values(&out, "LINE", "21"), ["50", "25"]);
+}
+
+#[test]
+fn closed_polyline_in_inches() {
+    let mut rp = RenderPaths::default();
+    let square = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0)];
+    for w in square.windows(2) {
+        rp.lines.push(RenderLine::new(
+            vec2(w[0].0, w[0].1),
+            vec2(w[1].0, w[1].1),
+            EdgeType::Visible,
+        ));
+    }
+    rp.merge_lines(1e-9);
+    let options = DxfOptions {
+        unit: Unit::In,
+        ..DxfOptions::new(Page::new(25.4, 25.4, Unit::Mm))
+    };
+    let out = dxf(&rp, &options);
+
+    assert!(out.windows(4).any(|w| w == ["9", "$INSUNITS", "70", "1"]));
+    assert_eq!(values(&out, "POLYLINE", "66"), ["1"]);
+    assert_eq!(values(&out, "POLYLINE", "70"), ["1"]);
+    assert_eq!(values(&out, "VERTEX", "10"), ["0", "1", "1", "0"]);
+    assert_eq!(values(&out, "VERTEX", "20"), ["0", "0", "1", "1"]);
+    assert_eq!(values(&out, "SEQEND", "8"), ["VISIBLE"]);
+    assert!(!out.contains(&"LWPOLYLINE".to_string()));
+}
+
+#[test]
+fn points_on_their_own_layer() {
+    let mut rp = RenderPaths::default();
+    rp.points.push(RenderPoint::new(vec2(0.0, 0.5)));
+    let out = dxf(&rp, &DxfOptions::new(Page::new(100.0, 50.0, Unit::Mm)));
+
+    assert_eq!(values(&out, "LAYER", "2"), ["0", "POINTS"]);
+    assert_eq!(values(&out, "POINT", "8"), ["POINTS"]);
+    assert_eq!(values(&out, "POINT", "10"), ["50"]);
+    assert_eq!(values(&out, "POINT", "20"), ["37.5"]);
+}
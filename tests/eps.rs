@@ -0,0 +1,73 @@
+use glm::vec2;
+use nalgebra_glm as glm;
+use vectorfoil::{
+    Color, EdgeType, LineStyle, Marker, MarkerOptions, Page, RenderLine, RenderPaths, RenderPoint,
+    SvgOptions, Unit,
+};
+
+fn eps(rp: &RenderPaths, options: &SvgOptions) -> String {
+    let mut out = vec![];
+    rp.write_eps(&mut out, options).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn bounding_box_of_lines() {
+    let mut rp = RenderPaths::default();
+    rp.lines
+        .push(RenderLine::new(vec2(-0.5, -0.5), vec2(0.5, 0.0), EdgeType::Visible));
+    let mut options = SvgOptions::with_page(Page::new(2.0, 2.0, Unit::In));
+    options
+        .edge_styles
+        .insert(EdgeType::Visible, LineStyle::new(Color::BLACK, 0.1));
+    let out = eps(&rp, &options);
+
+    // The line runs from (36, 36) to (108, 72) points, and is 7.2
+    // points wide.
+    assert!(out.starts_with("%!PS-Adobe-3.0 EPSF-3.0\n%%BoundingBox: 32 32 112 76\n"));
+    assert!(out.contains("%%HiResBoundingBox: 32.4 32.4 111.6 75.6\n"));
+    assert!(out.contains("newpath\n36 36 moveto\n108 72 lineto\nstroke\n"));
+    assert!(out.ends_with("showpage\n%%EOF\n"));
+}
+
+#[test]
+fn styles_per_edge_type() {
+    let mut rp = RenderPaths::default();
+    rp.lines
+        .push(RenderLine::new(vec2(-1.0, -1.0), vec2(1.0, 1.0), EdgeType::Visible));
+    rp.lines
+        .push(RenderLine::new(vec2(-1.0, 1.0), vec2(0.0, 0.0), EdgeType::Hidden));
+    let out = eps(&rp, &SvgOptions::with_page(Page::new(1.0, 1.0, Unit::In)));
+
+    let hidden = out
+        .find("0.133 0.133 0.8 setrgbcolor 0.375 setlinewidth [1.5 0.75] 0 setdash")
+        .unwrap();
+    let visible = out
+        .find("0.267 0.267 0.267 setrgbcolor 0.75 setlinewidth [] 0 setdash")
+        .unwrap();
+    assert!(hidden < visible);
+}
+
+#[test]
+fn point_markers_in_bounding_box() {
+    let mut rp = RenderPaths::default();
+    rp.points.push(RenderPoint::new(vec2(0.5, 0.5)));
+    let mut options = SvgOptions::with_page(Page::new(2.0, 2.0, Unit::In));
+    options.markers = MarkerOptions {
+        shape: Marker::Cross,
+        size: 0.1,
+        stroke_width: 0.02,
+        ..MarkerOptions::default()
+    };
+    let out = eps(&rp, &options);
+
+    // The cross is centred at (108, 108) points, 7.2 points in radius
+    // and 1.44 points wide.
+    assert!(out.contains("%%HiResBoundingBox: 100.08 100.08 115.92 115.92\n"));
+    assert!(out.contains("newpath\n100.8 100.8 moveto\n115.2 115.2 lineto\n"));
+
+    options.markers.shape = Marker::Circle;
+    let out = eps(&rp, &options);
+    assert!(out.contains("%%HiResBoundingBox: 100.8 100.8 115.2 115.2\n"));
+    assert!(out.contains("newpath\n108 108 7.2 0 360 arc\nclosepath\nfill\n"));
+}
@@ -0,0 +1,72 @@
+use assert_approx_eq::assert_approx_eq;
+use glm::{look_at, ortho, vec2, vec3};
+use nalgebra_glm as glm;
+use vectorfoil::face::merge_triangles;
+use vectorfoil::{Attributes, Color, Renderer, SvgOptions};
+
+/// Orthographic renderer, where NDC = world / 2.
+fn renderer() -> Renderer {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = ortho(-2.0, 2.0, -2.0, 2.0, 0.1, 10.0);
+    Renderer::new(&(proj * view))
+}
+
+#[test]
+fn merge_t_junction() {
+    // a unit square, with one edge split by a t-junction
+    let loops = merge_triangles(&[
+        [vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(0.5, 0.5)],
+        [vec2(1.0, 0.0), vec2(1.0, 1.0), vec2(0.5, 0.5)],
+        [vec2(0.5, 0.5), vec2(1.0, 1.0), vec2(0.0, 1.0)],
+        [vec2(0.0, 0.0), vec2(0.5, 0.5), vec2(0.0, 0.5)],
+        [vec2(0.0, 0.5), vec2(0.5, 0.5), vec2(0.0, 1.0)],
+    ]);
+    assert_eq!(loops.len(), 1);
+    assert_eq!(loops[0].len(), 4);
+}
+
+#[test]
+fn occluded_polygon() {
+    let mut r = renderer();
+    let back = r.add_polygon(&[
+        vec3(-1.0, -1.0, -1.0),
+        vec3(1.0, -1.0, -1.0),
+        vec3(1.0, 1.0, -1.0),
+        vec3(-1.0, 1.0, -1.0),
+    ]);
+    r.set_attributes(Attributes {
+        fill: Some(Color::rgb(255, 0, 0)),
+        ..Default::default()
+    });
+    let front = r.add_triangle(
+        vec3(-0.5, -0.5, 1.0),
+        vec3(0.5, -0.5, 1.0),
+        vec3(0.0, 0.5, 1.0),
+    );
+    let rp = r.render();
+
+    assert_eq!(rp.faces.len(), 2);
+    let back_face = rp.faces.iter().find(|f| f.id() == Some(back)).unwrap();
+    let front_face = rp.faces.iter().find(|f| f.id() == Some(front)).unwrap();
+
+    // the square has a triangular hole
+    assert_eq!(back_face.loops().len(), 2);
+    assert_approx_eq!(back_face.area(), 1.0 - 0.125);
+    assert_approx_eq!(front_face.area(), 0.125);
+    assert!(front_face.depth() < back_face.depth());
+    // back to front
+    assert_eq!(rp.faces[0].id(), Some(back));
+
+    let opt = SvgOptions {
+        faces: true,
+        ..SvgOptions::new(100.0, 100.0)
+    };
+    let svg = rp.as_standalone_svg(&opt).to_string();
+    assert_eq!(svg.matches("class=\"face\"").count(), 2);
+    assert!(svg.contains(".face { fill: #ffffff; stroke: none; }"));
+    assert!(svg.contains("style=\"fill:#ff0000\""));
+}
@@ -0,0 +1,82 @@
+use glm::vec2;
+use nalgebra_glm as glm;
+use vectorfoil::{
+    EdgeType, GcodeOptions, LayerSplit, Origin, Page, PenLift, RenderLine, RenderPaths,
+    RenderPoint, SvgOptions, Unit,
+};
+
+fn paths() -> RenderPaths {
+    let mut rp = RenderPaths::default();
+    rp.lines
+        .push(RenderLine::new(vec2(-1.0, -1.0), vec2(1.0, 1.0), EdgeType::Invisible));
+    rp.lines
+        .push(RenderLine::new(vec2(-1.0, 1.0), vec2(0.0, 0.0), EdgeType::Visible));
+    rp
+}
+
+#[test]
+fn servo_commands() {
+    let options = GcodeOptions::new(Page::new(100.0, 50.0, Unit::Mm));
+    let mut out = vec![];
+    paths().write_gcode(&mut out, &options).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "G21\nG90\nM3 S0\n\
+         G0 X0.000 Y0.000\nM3 S1000\nG1 X100.000 Y50.000 F1000\nM3 S0\n\
+         G0 X0.000 Y50.000\nM3 S1000\nG1 X50.000 Y25.000 F1000\nM3 S0\n\
+         G0 X0 Y0\n"
+    );
+}
+
+#[test]
+fn z_lift_and_origin() {
+    let options = GcodeOptions {
+        origin: Origin::TopLeft,
+        pen: PenLift::Z { up: 5.0, down: 0.0 },
+        travel_rate: Some(3000.0),
+        ..GcodeOptions::new(Page::new(1.0, 1.0, Unit::In))
+    };
+    assert_eq!(options.to_machine(vec2(1.0, -1.0)), vec2(25.4, -25.4));
+
+    let mut out = vec![];
+    paths().write_gcode(&mut out, &options).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("G1 X0.000 Y-25.400 F3000\nG0 Z0.000\n"));
+    assert!(out.contains("G0 Z5.000\n"));
+}
+
+#[test]
+fn points_as_plunges() {
+    let mut rp = RenderPaths::default();
+    rp.points.push(RenderPoint::new(vec2(0.0, 0.0)));
+    let options = GcodeOptions::new(Page::new(100.0, 50.0, Unit::Mm));
+    let mut out = vec![];
+    rp.write_gcode(&mut out, &options).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "G21\nG90\nM3 S0\nG0 X50.000 Y25.000\nM3 S1000\nM3 S0\nG0 X0 Y0\n"
+    );
+}
+
+#[test]
+fn one_program_per_layer() {
+    let mut rp = paths();
+    rp.points.push(RenderPoint::new(vec2(0.0, 0.0)));
+    let options = GcodeOptions::new(Page::new(100.0, 50.0, Unit::Mm));
+    let mut layers = SvgOptions::with_page(options.page);
+    layers.edge_names.insert(EdgeType::Visible, "front".to_string());
+    let mut opened = vec![];
+    let written = rp
+        .write_gcode_layers(&options, &layers, LayerSplit::EdgeType, |name| {
+            opened.push(name.to_string());
+            Ok(Vec::new())
+        })
+        .unwrap();
+    let names: Vec<&str> = written.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, opened);
+    assert_eq!(names, ["invisible", "front", "points"]);
+    for (_, out) in written {
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches("M3 S1000").count(), 1);
+    }
+}
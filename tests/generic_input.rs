@@ -0,0 +1,59 @@
+use glm::{look_at, perspective, vec3};
+use nalgebra_glm as glm;
+use vectorfoil::Renderer;
+
+fn render_f64() -> vectorfoil::RenderPaths {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = perspective(1.0, std::f64::consts::FRAC_PI_2, 0.1, 10.0);
+    let mut r = Renderer::new(&(proj * view));
+    r.add_point(vec3(0.25, 0.25, 0.0));
+    r.add_line(vec3(-1.0, 0.5, 0.0), vec3(1.0, 0.5, 0.0));
+    r.add_triangle(
+        vec3(-1.0, -1.0, 1.0),
+        vec3(0.5, 0.0, 1.0),
+        vec3(-1.0, 1.0, 1.0),
+    );
+    r.add_polygon(&[
+        vec3(-0.5, 0.0, -1.0),
+        vec3(1.0, -1.0, -1.0),
+        vec3(1.0, 1.0, -1.0),
+    ]);
+    r.render()
+}
+
+#[test]
+fn f32_input() {
+    let view = look_at(
+        &vec3(0.0f32, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = perspective(1.0f32, std::f32::consts::FRAC_PI_2, 0.1, 10.0);
+    let mut r = Renderer::new(&(proj * view));
+    r.add_point(vec3(0.25f32, 0.25, 0.0));
+    r.add_line([-1.0f32, 0.5, 0.0], [1.0f32, 0.5, 0.0]);
+    r.add_triangle(
+        vec3(-1.0f32, -1.0, 1.0),
+        vec3(0.5, 0.0, 1.0),
+        vec3(-1.0, 1.0, 1.0),
+    );
+    r.add_polygon(&[
+        vec3(-0.5f32, 0.0, -1.0),
+        vec3(1.0, -1.0, -1.0),
+        vec3(1.0, 1.0, -1.0),
+    ]);
+    let rp = r.render();
+    let expected = render_f64();
+
+    assert_eq!(rp.points.len(), expected.points.len());
+    assert_eq!(rp.lines.len(), expected.lines.len());
+    for (a, b) in rp.lines.iter().zip(expected.lines.iter()) {
+        assert!((a.points()[0] - b.points()[0]).norm() < 1e-5);
+        assert!((a.points()[1] - b.points()[1]).norm() < 1e-5);
+        assert_eq!(a.edge(), b.edge());
+    }
+}
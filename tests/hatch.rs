@@ -0,0 +1,120 @@
+use assert_approx_eq::assert_approx_eq;
+use glm::{look_at, ortho, vec2, vec3};
+use nalgebra_glm as glm;
+use vectorfoil::hatch::hatch_loops;
+use vectorfoil::{Attributes, EdgeType, HatchOptions, Light, Renderer};
+
+/// Orthographic renderer, where NDC = world / 2.
+fn renderer() -> Renderer {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = ortho(-2.0, 2.0, -2.0, 2.0, 0.1, 10.0);
+    Renderer::new(&(proj * view))
+}
+
+#[test]
+fn hatch_square_with_hole() {
+    let outer = vec![
+        vec2(0.0, 0.0),
+        vec2(1.0, 0.0),
+        vec2(1.0, 1.0),
+        vec2(0.0, 1.0),
+    ];
+    let hole = vec![
+        vec2(0.25, 0.25),
+        vec2(0.25, 0.75),
+        vec2(0.75, 0.75),
+        vec2(0.75, 0.25),
+    ];
+    // horizontal lines at y = 0.1, 0.2, ..., 0.9
+    let lines = hatch_loops(std::slice::from_ref(&outer), 0.0, 0.1);
+    assert_eq!(lines.len(), 9);
+    for l in &lines {
+        assert_approx_eq!(l[0].x, 0.0);
+        assert_approx_eq!(l[1].x, 1.0);
+    }
+
+    // the lines through the hole are split in two
+    let lines = hatch_loops(&[outer, hole], 0.0, 0.1);
+    assert_eq!(lines.len(), 9 + 5);
+    let total: f64 = lines.iter().map(|l| (l[1] - l[0]).norm()).sum();
+    assert_approx_eq!(total, 9.0 - 5.0 * 0.5);
+}
+
+#[test]
+fn hatching_follows_tone() {
+    let square = |r: &mut Renderer, x: f64| {
+        r.add_polygon(&[
+            vec3(x, 0.0, 0.0),
+            vec3(x + 1.0, 0.0, 0.0),
+            vec3(x + 1.0, 1.0, 0.0),
+            vec3(x, 1.0, 0.0),
+        ])
+    };
+    let mut r = renderer();
+    r.set_attributes(Attributes {
+        tone: Some(0.0),
+        ..Default::default()
+    });
+    let dark = square(&mut r, -2.0);
+    r.set_attributes(Attributes {
+        tone: Some(0.5),
+        ..Default::default()
+    });
+    let light = square(&mut r, 0.0);
+    r.set_attributes(None);
+    let unlit = square(&mut r, 1.0);
+
+    let mut rp = r.render();
+    let options = HatchOptions {
+        cross_hatch: Some(0.25),
+        ..HatchOptions::new(0.02)
+    };
+    rp.add_hatching(&options);
+
+    let length = |id| -> f64 {
+        rp.lines_of_type(EdgeType::Hatch)
+            .filter(|l| l.id() == Some(id))
+            .map(|l| l.length())
+            .sum()
+    };
+    // the dark face is cross-hatched at twice the density
+    assert!(length(dark) > 3.5 * length(light));
+    assert_eq!(length(unlit), 0.0);
+}
+
+#[test]
+fn hatching_follows_light() {
+    // Two faces of the same projected size: one facing the camera,
+    // and one turned away from the light.
+    let mut r = renderer();
+    let flat = r.add_polygon(&[
+        vec3(-2.0, 0.0, 0.0),
+        vec3(-1.5, 0.0, 0.0),
+        vec3(-1.5, 1.0, 0.0),
+        vec3(-2.0, 1.0, 0.0),
+    ]);
+    let turned = r.add_polygon(&[
+        vec3(0.5, 0.0, -0.866),
+        vec3(1.0, 0.0, 0.0),
+        vec3(1.0, 1.0, 0.0),
+        vec3(0.5, 1.0, -0.866),
+    ]);
+    let mut rp = r.render();
+    let options = HatchOptions {
+        light: Some(Light::new(vec3(1.0, 0.0, 1.0))),
+        ..HatchOptions::new(0.02)
+    };
+    rp.add_hatching(&options);
+
+    let count = |id| {
+        rp.lines_of_type(EdgeType::Hatch)
+            .filter(|l| l.id() == Some(id))
+            .count()
+    };
+    assert!(count(flat) > 0);
+    assert!(count(turned) > 2 * count(flat), "{} {}", count(turned), count(flat));
+}
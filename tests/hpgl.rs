@@ -0,0 +1,66 @@
+use glm::vec2;
+use nalgebra_glm as glm;
+use std::collections::BTreeMap;
+use vectorfoil::{
+    EdgeType, HpglOptions, Page, PenSelect, RenderLine, RenderPaths, RenderPoint, Unit,
+};
+
+fn paths() -> RenderPaths {
+    let mut rp = RenderPaths::default();
+    rp.lines
+        .push(RenderLine::new(vec2(-1.0, -1.0), vec2(1.0, 1.0), EdgeType::Hidden));
+    rp.lines
+        .push(RenderLine::new(vec2(-1.0, 1.0), vec2(0.0, 0.0), EdgeType::Visible));
+    rp
+}
+
+fn hpgl(options: &HpglOptions) -> String {
+    let mut out = vec![];
+    paths().write_hpgl(&mut out, options).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn single_pen() {
+    let options = HpglOptions::new(Page::new(100.0, 50.0, Unit::Mm));
+    assert_eq!(
+        hpgl(&options),
+        "IN;\nSP1;\nPU0,0;PD4000,2000;\nPU0,2000;PD2000,1000;\nPU;SP0;\n"
+    );
+}
+
+#[test]
+fn pens_by_edge_type() {
+    let mut pens = BTreeMap::new();
+    pens.insert(EdgeType::Visible, 2);
+    let options = HpglOptions {
+        pens: PenSelect::ByEdgeType(pens),
+        default_pen: 3,
+        units_per_mm: 10.0,
+        ..HpglOptions::new(Page::new(1.0, 1.0, Unit::In))
+    };
+    assert_eq!(
+        hpgl(&options),
+        "IN;\nSP2;\nPU0,254;PD127,127;\nSP3;\nPU0,0;PD254,254;\nPU;SP0;\n"
+    );
+}
+
+#[test]
+fn points_as_dots() {
+    let mut rp = paths();
+    rp.points.push(RenderPoint::new(vec2(0.5, 0.5)));
+    let mut pens = BTreeMap::new();
+    pens.insert(EdgeType::Hidden, 2);
+    let options = HpglOptions {
+        pens: PenSelect::ByEdgeType(pens),
+        ..HpglOptions::new(Page::new(100.0, 50.0, Unit::Mm))
+    };
+    let mut out = vec![];
+    rp.write_hpgl(&mut out, &options).unwrap();
+    // points have no edge type, so they take the default pen
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "IN;\nSP1;\nPU0,2000;PD2000,1000;\nPU3000,1500;PD;PU;\n\
+         SP2;\nPU0,0;PD4000,2000;\nPU;SP0;\n"
+    );
+}
@@ -0,0 +1,57 @@
+use glm::{look_at, perspective, vec3};
+use nalgebra_glm as glm;
+use vectorfoil::{Renderer, SvgOptions};
+
+fn renderer() -> Renderer {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = perspective(1.0, std::f64::consts::FRAC_PI_2, 0.1, 10.0);
+    Renderer::new(&(proj * view))
+}
+
+#[test]
+fn ids_survive_splitting() {
+    let mut r = renderer();
+    r.set_object(7);
+    let front = r.add_triangle(
+        vec3(-1.0, -1.0, 1.0),
+        vec3(0.5, 0.0, 1.0),
+        vec3(-1.0, 1.0, 1.0),
+    );
+    r.set_object(8);
+    let back = r.add_polygon(&[
+        vec3(-0.5, 0.0, -1.0),
+        vec3(1.0, -1.0, -1.0),
+        vec3(1.0, 1.0, -1.0),
+        vec3(-0.5, 1.0, -1.0),
+    ]);
+    r.set_object(None);
+    let line = r.add_line(vec3(-1.0, -1.0, 0.0), vec3(1.0, 1.0, 0.0));
+    assert_ne!(front, back);
+
+    let rp = r.render();
+    for l in &rp.lines {
+        let id = l.id().unwrap();
+        if id == front {
+            assert_eq!(l.object(), Some(7));
+        } else if id == back {
+            assert_eq!(l.object(), Some(8));
+        } else {
+            assert_eq!(id, line);
+            assert_eq!(l.object(), None);
+        }
+    }
+    assert_eq!(rp.lines.iter().filter(|l| l.id() == Some(front)).count(), 3);
+    assert!(rp.lines.iter().filter(|l| l.id() == Some(back)).count() > 6);
+
+    let opt = SvgOptions {
+        by_object: true,
+        ..SvgOptions::new(100.0, 100.0)
+    };
+    let svg = rp.as_standalone_svg(&opt).to_string();
+    assert!(svg.contains("id=\"object-7\""));
+    assert!(svg.contains("id=\"object-8\""));
+}
@@ -0,0 +1,120 @@
+use glm::{look_at, ortho, vec3};
+use nalgebra_glm as glm;
+use vectorfoil::render_paths::layer_label;
+use vectorfoil::{Attributes, Color, LayerSplit, RenderPaths, Renderer, SvgOptions};
+
+/// A red square on layer "back", and a triangle in front of it.
+fn paths() -> RenderPaths {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = ortho(-2.0, 2.0, -2.0, 2.0, 0.1, 10.0);
+    let mut r = Renderer::new(&(proj * view));
+    r.set_attributes(Attributes {
+        stroke: Some(Color::rgb(255, 0, 0)),
+        layer: Some("back".to_string()),
+        ..Default::default()
+    });
+    r.add_polygon(&[
+        vec3(-1.0, -1.0, -1.0),
+        vec3(1.0, -1.0, -1.0),
+        vec3(1.0, 1.0, -1.0),
+        vec3(-1.0, 1.0, -1.0),
+    ]);
+    r.set_attributes(None);
+    r.add_triangle(
+        vec3(-0.5, -0.5, 1.0),
+        vec3(0.5, -0.5, 1.0),
+        vec3(0.0, 0.5, 1.0),
+    );
+    r.add_point(vec3(1.5, 1.5, 0.0));
+    r.render()
+}
+
+fn svg(split: LayerSplit) -> String {
+    let opt = SvgOptions {
+        inkscape_layers: Some(split),
+        faces: true,
+        ..SvgOptions::new(100.0, 100.0)
+    };
+    paths().as_standalone_svg(&opt).to_string()
+}
+
+/// Nesting depth, below `<svg>`, of each layer group in `svg`.
+fn layer_depths(svg: &str) -> Vec<usize> {
+    let mut depths = vec![];
+    let mut depth = 0;
+    let mut rest = svg;
+    while let Some(i) = rest.find('<') {
+        rest = &rest[i..];
+        let tag = &rest[..rest.find('>').unwrap() + 1];
+        if tag.starts_with("</g") {
+            depth -= 1;
+        } else if tag.starts_with("<g ") || tag == "<g>" {
+            if tag.contains("inkscape:groupmode=\"layer\"") {
+                depths.push(depth);
+            }
+            if !tag.ends_with("/>") {
+                depth += 1;
+            }
+        }
+        rest = &rest[tag.len()..];
+    }
+    depths
+}
+
+#[test]
+fn layers_by_edge_type() {
+    let svg = svg(LayerSplit::EdgeType);
+    assert!(svg.contains("xmlns:inkscape=\"http://www.inkscape.org/namespaces/inkscape\""));
+    assert_eq!(svg.matches("inkscape:groupmode=\"layer\"").count(), 5);
+    // Inkscape only sees layers at the top level of the document.
+    assert_eq!(layer_depths(&svg), [0; 5]);
+    let labels = ["faces", "split", "invisible", "visible", "points"];
+    for (i, label) in labels.iter().enumerate() {
+        assert!(svg.contains(&format!(
+            "id=\"layer{}\" inkscape:groupmode=\"layer\" inkscape:label=\"{}\"",
+            i + 1,
+            label
+        )));
+    }
+}
+
+#[test]
+fn layers_by_user_layer_and_color() {
+    let rp = paths();
+    let opt = SvgOptions::new(100.0, 100.0);
+    let layers = rp.line_layers(LayerSplit::UserLayer, &opt);
+    let names: Vec<&str> = layers.iter().map(|(n, _)| n.as_str()).collect();
+    // unlayered lines are painted first, as with `by_layer`
+    assert_eq!(names, ["default", "back"]);
+    assert_eq!(
+        layers.iter().map(|(_, l)| l.len()).sum::<usize>(),
+        rp.lines.len()
+    );
+
+    let layers = rp.line_layers(LayerSplit::Color, &opt);
+    let names: Vec<&str> = layers.iter().map(|(n, _)| n.as_str()).collect();
+    assert!(names.contains(&"#ff0000"));
+    assert!(names.contains(&"#444444"));
+
+    let svg = svg(LayerSplit::Color);
+    assert!(svg.contains("inkscape:label=\"#ff0000\""));
+    assert!(layer_depths(&svg).iter().all(|d| *d == 0));
+}
+
+#[test]
+fn reserved_layer_names() {
+    let mut rp = paths();
+    rp.attributes[0].layer = Some("points".to_string());
+    let opt = SvgOptions::new(100.0, 100.0);
+    let layers = rp.line_layers(LayerSplit::UserLayer, &opt);
+    let names: Vec<&str> = layers.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, ["default", "points (layer)"]);
+
+    assert_eq!(layer_label("default"), "default (layer)");
+    assert_eq!(layer_label("faces (layer)"), "faces (layer) (layer)");
+    assert_eq!(layer_label("back"), "back");
+}
@@ -0,0 +1,63 @@
+use assert_approx_eq::assert_approx_eq;
+use glm::{look_at, perspective, translation, vec3};
+use nalgebra_glm as glm;
+use std::sync::Arc;
+use vectorfoil::{Mesh, Renderer};
+
+fn renderer() -> Renderer {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = perspective(1.0, std::f64::consts::FRAC_PI_2, 0.1, 10.0);
+    Renderer::new(&(proj * view))
+}
+
+#[test]
+fn instance_matches_pretransformed() {
+    let mut mesh = Mesh::new();
+    mesh.add_line(vec3(0.0, 0.0, 0.0), vec3(0.5, 0.0, 0.0));
+    let tri = mesh.add_triangle(
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.5, 0.0, 0.0),
+        vec3(0.0, 0.5, 0.0),
+    );
+    let mesh = Arc::new(mesh);
+
+    let mut r = renderer();
+    r.set_object(1);
+    let first = r.add_instance(&mesh, &translation(&vec3(-1.0, 0.0, 0.0)));
+    r.set_object(2);
+    let second = r.add_instance(&mesh, &translation(&vec3(1.0, 0.0, 0.0)));
+    assert_eq!(second, first + mesh.num_ids());
+    let rp = r.render();
+
+    let mut r = renderer();
+    for dx in &[-1.0, 1.0] {
+        r.add_line(vec3(*dx, 0.0, 0.0), vec3(dx + 0.5, 0.0, 0.0));
+        r.add_triangle(
+            vec3(*dx, 0.0, 0.0),
+            vec3(dx + 0.5, 0.0, 0.0),
+            vec3(*dx, 0.5, 0.0),
+        );
+    }
+    let expected = r.render();
+
+    assert_eq!(rp.lines.len(), 8);
+    assert_eq!(rp.lines.len(), expected.lines.len());
+    for (a, b) in rp.lines.iter().zip(expected.lines.iter()) {
+        for i in 0..2 {
+            assert_approx_eq!(a.points()[i].x, b.points()[i].x);
+            assert_approx_eq!(a.points()[i].y, b.points()[i].y);
+        }
+        assert_eq!(a.id(), b.id());
+    }
+
+    assert_eq!(rp.lines_of_object(1).count(), 4);
+    assert_eq!(rp.lines_of_object(2).count(), 4);
+    assert_eq!(
+        rp.lines.iter().filter(|l| l.id() == Some(second + tri)).count(),
+        3
+    );
+}
@@ -0,0 +1,108 @@
+//! Input and output through the optional math library features.
+use glm::{look_at, perspective, vec3};
+use nalgebra_glm as glm;
+use vectorfoil::{RenderPaths, Renderer};
+
+fn clip() -> glm::DMat4 {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = perspective(1.0, std::f64::consts::FRAC_PI_2, 0.1, 10.0);
+    proj * view
+}
+
+fn expected() -> RenderPaths {
+    let mut r = Renderer::new(&clip());
+    r.add_point(vec3(0.25, 0.25, 0.0));
+    r.add_triangle(
+        vec3(-1.0, -1.0, 1.0),
+        vec3(0.5, 0.0, 1.0),
+        vec3(-1.0, 1.0, 1.0),
+    );
+    r.render()
+}
+
+#[allow(unused)]
+fn assert_same(rp: &RenderPaths) {
+    let expected = expected();
+    assert_eq!(rp.lines.len(), expected.lines.len());
+    for (a, b) in rp.lines.iter().zip(expected.lines.iter()) {
+        assert!((a.points()[0] - b.points()[0]).norm() < 1e-5);
+        assert!((a.points()[1] - b.points()[1]).norm() < 1e-5);
+    }
+}
+
+#[test]
+fn nalgebra_output() {
+    let rp = expected();
+    let p: Vec<[f64; 2]> = rp.points_as();
+    let q = rp.points[0].point();
+    assert_eq!(p[0], [q.x, q.y]);
+}
+
+#[cfg(feature = "mint")]
+#[test]
+fn mint_input() {
+    let c = clip();
+    let m: mint::ColumnMatrix4<f64> = mint::ColumnMatrix4 {
+        x: [c[(0, 0)], c[(1, 0)], c[(2, 0)], c[(3, 0)]].into(),
+        y: [c[(0, 1)], c[(1, 1)], c[(2, 1)], c[(3, 1)]].into(),
+        z: [c[(0, 2)], c[(1, 2)], c[(2, 2)], c[(3, 2)]].into(),
+        w: [c[(0, 3)], c[(1, 3)], c[(2, 3)], c[(3, 3)]].into(),
+    };
+    let mut r = Renderer::new(&m);
+    r.add_point(mint::Point3::from([0.25f32, 0.25, 0.0]));
+    r.add_triangle(
+        mint::Vector3::from([-1.0f32, -1.0, 1.0]),
+        mint::Vector3::from([0.5f32, 0.0, 1.0]),
+        mint::Vector3::from([-1.0f32, 1.0, 1.0]),
+    );
+    let rp = r.render();
+    assert_same(&rp);
+    let p: Vec<mint::Point2<f64>> = rp.points_as();
+    assert_eq!(p.len(), 1);
+}
+
+#[cfg(feature = "glam")]
+#[test]
+fn glam_input() {
+    // Built from the column-major elements, as the glam camera
+    // helpers differ between versions.
+    let c = glam::Mat4::from_cols_slice(clip().cast::<f32>().as_slice());
+    let mut r = Renderer::new(&c);
+    r.add_point(glam::Vec3::new(0.25, 0.25, 0.0));
+    r.add_triangle(
+        glam::Vec3::new(-1.0, -1.0, 1.0),
+        glam::Vec3::new(0.5, 0.0, 1.0),
+        glam::Vec3::new(-1.0, 1.0, 1.0),
+    );
+    let rp = r.render();
+    assert_same(&rp);
+    let p: Vec<glam::DVec2> = rp.points_as();
+    assert!((p[0].x - expected().points[0].point().x).abs() < 1e-5);
+}
+
+#[cfg(feature = "cgmath")]
+#[test]
+fn cgmath_input() {
+    use cgmath::{Deg, Matrix4, Point3, Vector3};
+    let c = cgmath::perspective(Deg(90.0), 1.0, 0.1, 10.0)
+        * Matrix4::look_at_rh(
+            Point3::new(0.0, 0.0, 5.0),
+            Point3::new(0.0, 0.0, 0.0),
+            Vector3::unit_y(),
+        );
+    let mut r = Renderer::new(&c);
+    r.add_point(Point3::new(0.25, 0.25, 0.0));
+    r.add_triangle(
+        Vector3::new(-1.0, -1.0, 1.0),
+        Vector3::new(0.5, 0.0, 1.0),
+        Vector3::new(-1.0, 1.0, 1.0),
+    );
+    let rp = r.render();
+    assert_same(&rp);
+    let p: Vec<cgmath::Point2<f64>> = rp.points_as();
+    assert_eq!(p.len(), 1);
+}
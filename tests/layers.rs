@@ -0,0 +1,81 @@
+use glm::{look_at, ortho, vec3};
+use nalgebra_glm as glm;
+use vectorfoil::{Attributes, EdgeType, RenderPaths, Renderer, SvgOptions};
+
+/// A square partly covered by a triangle, on two user layers.
+fn paths() -> RenderPaths {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = ortho(-2.0, 2.0, -2.0, 2.0, 0.1, 10.0);
+    let mut r = Renderer::new(&(proj * view));
+    r.set_attributes(Attributes {
+        layer: Some("back".to_string()),
+        ..Default::default()
+    });
+    r.add_polygon(&[
+        vec3(-1.0, -1.0, -1.0),
+        vec3(1.0, -1.0, -1.0),
+        vec3(1.0, 1.0, -1.0),
+        vec3(-1.0, 1.0, -1.0),
+    ]);
+    r.set_attributes(Attributes {
+        layer: Some("front".to_string()),
+        ..Default::default()
+    });
+    r.add_triangle(
+        vec3(-0.5, -0.5, 1.0),
+        vec3(0.5, -0.5, 1.0),
+        vec3(0.0, 0.5, 1.0),
+    );
+    r.render()
+}
+
+fn position(svg: &str, pat: &str) -> usize {
+    svg.find(pat).unwrap_or_else(|| panic!("{} not found", pat))
+}
+
+#[test]
+fn stable_layer_order() {
+    let rp = paths();
+    assert!(rp.lines_of_type(EdgeType::Invisible).count() > 0);
+    let opt = SvgOptions {
+        by_layer: true,
+        ..SvgOptions::new(100.0, 100.0)
+    };
+    let svg = rp.as_standalone_svg(&opt).to_string();
+    for _ in 0..5 {
+        assert_eq!(paths().as_standalone_svg(&opt).to_string(), svg);
+    }
+
+    // invisible lines are painted below the visible ones, and layers in
+    // the order they were set
+    let back = position(&svg, "data-layer=\"back\"");
+    let front = position(&svg, "data-layer=\"front\"");
+    assert!(back < front);
+    let invisible = position(&svg[back..], "<g class=\"invisible\"");
+    let visible = position(&svg[back..], "<g class=\"visible\"");
+    assert!(invisible < visible);
+}
+
+#[test]
+fn custom_layer_order() {
+    let mut opt = SvgOptions {
+        by_layer: true,
+        edge_order: vec![EdgeType::Visible, EdgeType::Invisible],
+        layer_order: vec!["front".to_string()],
+        ..SvgOptions::new(100.0, 100.0)
+    };
+    opt.edge_names.insert(EdgeType::Visible, "outline".to_string());
+    let svg = paths().as_standalone_svg(&opt).to_string();
+
+    let back = position(&svg, "data-layer=\"back\"");
+    let front = position(&svg, "data-layer=\"front\"");
+    assert!(front < back);
+    let invisible = position(&svg[back..], "<g class=\"invisible\"");
+    let visible = position(&svg[back..], "<g class=\"visible\"");
+    assert!(visible < invisible);
+    assert!(svg.contains("data-name=\"outline\""));
+}
@@ -0,0 +1,63 @@
+use assert_approx_eq::assert_approx_eq;
+use glm::{look_at, perspective, vec3};
+use nalgebra_glm as glm;
+use vectorfoil::{Attributes, Marker, MarkerOptions, Renderer, SvgOptions};
+
+fn renderer() -> Renderer {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = perspective(1.0, std::f64::consts::FRAC_PI_2, 0.1, 10.0);
+    Renderer::new(&(proj * view))
+}
+
+#[test]
+fn points_as_markers() {
+    let mut r = renderer();
+    let near = r.add_point(vec3(0.0, 0.0, 2.0));
+    r.set_attributes(Attributes {
+        class: Some("anchor".to_string()),
+        ..Default::default()
+    });
+    let far = r.add_point(vec3(0.5, 0.5, -2.0));
+    let rp = r.render();
+
+    assert_eq!(rp.points.len(), 2);
+    let point = |id| rp.points.iter().find(|p| p.id() == Some(id)).unwrap();
+    assert!(point(near).depth() < point(far).depth());
+    assert!(point(near).w() < point(far).w());
+
+    // circles by default
+    let svg = rp
+        .as_standalone_svg(&SvgOptions::new(100.0, 100.0))
+        .to_string();
+    assert_eq!(svg.matches("<circle").count(), 2);
+    assert!(svg.contains("class=\"point circle anchor\""));
+
+    for (shape, tag) in [(Marker::Cross, "<path"), (Marker::Square, "<rect")].iter() {
+        let opt = SvgOptions {
+            markers: MarkerOptions {
+                shape: *shape,
+                class: "marker".to_string(),
+                ..Default::default()
+            },
+            ..SvgOptions::new(100.0, 100.0)
+        };
+        let svg = rp.as_standalone_svg(&opt).to_string();
+        assert_eq!(svg.matches(tag).count(), 2);
+        assert!(svg.contains(&format!("class=\"marker {}\"", shape.class_name())));
+    }
+
+    // nearer points get larger markers
+    let markers = MarkerOptions {
+        size: 0.01,
+        depth_scale: Some(0.5),
+        ..Default::default()
+    };
+    let (n, f) = (markers.point_size(point(near)), markers.point_size(point(far)));
+    assert!(n > f);
+    assert!(f >= 0.005 && n <= 0.01);
+    assert_approx_eq!(MarkerOptions::default().point_size(point(far)), 1.5);
+}
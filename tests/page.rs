@@ -0,0 +1,60 @@
+use assert_approx_eq::assert_approx_eq;
+use glm::vec2;
+use nalgebra_glm as glm;
+use vectorfoil::{Fit, Margins, Orientation, Page, PaperSize, RenderPaths, SvgOptions, Unit};
+
+#[test]
+fn paper_sizes() {
+    let a4 = Page::paper(PaperSize::A4, Orientation::Landscape);
+    assert_eq!((a4.width, a4.height, a4.unit), (297.0, 210.0, Unit::Mm));
+    assert_approx_eq!(Unit::In.convert(1.0, Unit::Mm), 25.4);
+    assert_approx_eq!(Unit::Mm.convert(25.4, Unit::Px), 96.0);
+}
+
+#[test]
+fn fit_to_page() {
+    let mut page = Page {
+        margins: Margins::uniform(10.0),
+        ..Page::new(220.0, 120.0, Unit::Mm)
+    };
+    // stretched over the area inside the margins
+    let corner = page.to_page(vec2(-1.0, 1.0));
+    assert_approx_eq!(corner.x, 10.0);
+    assert_approx_eq!(corner.y, 10.0);
+    let corner = page.to_page(vec2(1.0, -1.0));
+    assert_approx_eq!(corner.x, 210.0);
+    assert_approx_eq!(corner.y, 110.0);
+
+    // a square image is centred without distortion
+    page.fit = Fit::Contain { aspect: 1.0 };
+    let (origin, size) = page.drawing_area();
+    assert_approx_eq!(size.x, 100.0);
+    assert_approx_eq!(size.y, 100.0);
+    assert_approx_eq!(origin.x, 60.0);
+    assert_approx_eq!(origin.y, 10.0);
+    let c = page.to_page(vec2(0.0, 0.0));
+    assert_approx_eq!(c.x, 110.0);
+    assert_approx_eq!(c.y, 60.0);
+
+    // degenerate aspects (say, from empty bounds) fall back to
+    // stretching, rather than dividing by zero
+    let stretched = Page {
+        fit: Fit::Stretch,
+        ..page
+    };
+    for aspect in [0.0, -1.0, f64::NAN, f64::INFINITY].iter().copied() {
+        page.fit = Fit::Contain { aspect };
+        assert_eq!(page.drawing_area(), stretched.drawing_area());
+    }
+}
+
+#[test]
+fn svg_units() {
+    let opt = SvgOptions::with_page(Page::paper(PaperSize::Letter, Orientation::Portrait));
+    let svg = RenderPaths::default().as_standalone_svg(&opt).to_string();
+    assert!(svg.contains("width=\"215.9mm\""));
+    assert!(svg.contains("height=\"279.4mm\""));
+    assert!(svg.contains("viewBox=\"0 0 215.9 279.4\""));
+    // the default styles are scaled to the page units
+    assert!(svg.contains(&format!("stroke-width: {};", Unit::Px.convert(1.0, Unit::Mm))));
+}
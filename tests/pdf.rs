@@ -0,0 +1,83 @@
+use glm::vec2;
+use nalgebra_glm as glm;
+use vectorfoil::{
+    write_pdf_pages, Color, EdgeType, LineStyle, Marker, MarkerOptions, Page, RenderLine,
+    RenderPaths, RenderPoint, SvgOptions, Unit,
+};
+
+fn pdf(pages: &[&RenderPaths], options: &SvgOptions) -> String {
+    let mut out = vec![];
+    write_pdf_pages(&mut out, pages, options).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn paths() -> RenderPaths {
+    let mut rp = RenderPaths::default();
+    rp.lines
+        .push(RenderLine::new(vec2(-1.0, -1.0), vec2(1.0, 1.0), EdgeType::Visible));
+    rp.lines
+        .push(RenderLine::new(vec2(-1.0, 1.0), vec2(0.0, 0.0), EdgeType::Hidden));
+    rp
+}
+
+#[test]
+fn one_page_in_points() {
+    let mut options = SvgOptions::with_page(Page::new(2.0, 1.0, Unit::In));
+    options
+        .edge_styles
+        .insert(EdgeType::Hidden, LineStyle::new(Color::rgb(255, 0, 0), 0.01).dashed(&[0.1, 0.05]));
+    let mut out = vec![];
+    paths().write_pdf(&mut out, &options).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert!(out.starts_with("%PDF-1.4\n"));
+    assert!(out.ends_with("%%EOF\n"));
+    assert!(out.contains("/MediaBox [0 0 144 72]"));
+    assert!(out.contains("/Count 1"));
+
+    // Hidden lines are painted before visible ones, with the dash
+    // pattern converted to points.
+    let hidden = out.find("1 0 0 RG 0.72 w [7.2 3.6] 0 d 0 J 0 j\n0 72 m\n72 36 l\nS\n");
+    let visible = out.find("0 0 m\n144 72 l\nS\n");
+    assert!(hidden.unwrap() < visible.unwrap());
+}
+
+#[test]
+fn xref_offsets() {
+    let rp = paths();
+    let out = pdf(&[&rp, &rp, &rp], &SvgOptions::new(100.0, 100.0));
+    assert!(out.contains("/Kids [3 0 R 5 0 R 7 0 R] /Count 3"));
+
+    let xref = out[out.rfind("startxref\n").unwrap() + 10..].lines().next().unwrap();
+    let xref: usize = xref.parse().unwrap();
+    let entries: Vec<&str> = out[xref..].lines().skip(3).take(8).collect();
+    for (i, entry) in entries.iter().enumerate() {
+        let offset: usize = entry[..10].parse().unwrap();
+        assert!(out[offset..].starts_with(&format!("{} 0 obj\n", i + 1)));
+    }
+}
+
+#[test]
+fn point_markers() {
+    let mut rp = RenderPaths::default();
+    rp.points.push(RenderPoint::new(vec2(0.0, 0.0)));
+    let mut options = SvgOptions::with_page(Page::new(2.0, 1.0, Unit::In));
+    options.markers = MarkerOptions {
+        shape: Marker::Square,
+        size: 0.1,
+        color: Color::rgb(255, 0, 0),
+        ..MarkerOptions::default()
+    };
+    let out = pdf(&[&rp], &options);
+    assert!(out.contains("1 0 0 rg\n64.8 28.8 14.4 14.4 re\nf\n"));
+
+    options.markers.shape = Marker::Cross;
+    options.markers.stroke_width = 0.01;
+    let out = pdf(&[&rp], &options);
+    assert!(out.contains("1 0 0 RG 0.72 w [] 0 d 0 J 0 j\n64.8 28.8 m\n79.2 43.2 l\n"));
+
+    options.markers.shape = Marker::Circle;
+    let out = pdf(&[&rp], &options);
+    assert!(out.contains("1 0 0 rg\n79.2 36 m\n79.2 39.976 75.976 43.2 72 43.2 c\n"));
+    assert_eq!(out.matches(" c\n").count(), 4);
+}
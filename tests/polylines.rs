@@ -0,0 +1,129 @@
+use assert_approx_eq::assert_approx_eq;
+use glm::{look_at, ortho, vec2, vec3, vec4};
+use nalgebra_glm as glm;
+use vectorfoil::dedup::DEFAULT_PRECEDENCE;
+use vectorfoil::polyline::merge_lines;
+use vectorfoil::{EdgeType, RenderLine, RenderPaths, Renderer, SvgOptions};
+
+#[test]
+fn chain_collinear_pieces() {
+    // a unit square, with the bottom edge cut into three pieces,
+    // given in no particular order or direction
+    let l = |a: (f64, f64), b: (f64, f64)| {
+        RenderLine::new(vec2(a.0, a.1), vec2(b.0, b.1), EdgeType::Visible)
+    };
+    let lines = [
+        l((1.0, 1.0), (0.0, 1.0)),
+        l((0.0, 0.0), (0.25, 0.0)),
+        l((1.0, 0.0), (0.6, 0.0)),
+        l((1.0, 0.0), (1.0, 1.0)),
+        l((0.25, 0.0), (0.6, 1e-7)),
+        l((0.0, 1.0), (0.0, 0.0)),
+        // on its own
+        l((2.0, 2.0), (3.0, 2.0)),
+    ];
+    let polylines = merge_lines(&lines, 1e-5);
+    assert_eq!(polylines.len(), 2);
+    let square = polylines.iter().find(|p| p.is_closed()).unwrap();
+    assert_eq!(square.points().len(), 5);
+    assert_approx_eq!(square.length(), 4.0, 1e-5);
+    let single = polylines.iter().find(|p| !p.is_closed()).unwrap();
+    assert_eq!(single.points().len(), 2);
+}
+
+#[test]
+fn merge_rendered_lines() {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = ortho(-2.0, 2.0, -2.0, 2.0, 0.1, 10.0);
+    let mut r = Renderer::new(&(proj * view));
+    r.add_polygon(&[
+        vec3(-1.0, -1.0, -1.0),
+        vec3(1.0, -1.0, -1.0),
+        vec3(1.0, 1.0, -1.0),
+        vec3(-1.0, 1.0, -1.0),
+    ]);
+    r.set_object(1);
+    r.add_triangle(
+        vec3(-0.5, -0.5, 1.0),
+        vec3(0.5, -0.5, 1.0),
+        vec3(0.0, 0.5, 1.0),
+    );
+    let mut rp = r.render().visible_only();
+    let count = rp.lines.len();
+    let length: f64 = rp.lines.iter().map(|l| l.length()).sum();
+
+    rp.merge_lines(1e-6);
+    assert!(rp.lines.is_empty());
+    assert!(rp.polylines.len() < count);
+    let merged: f64 = rp.polylines.iter().map(|p| p.length()).sum();
+    assert_approx_eq!(merged, length, 1e-6);
+
+    // the queries by type and object move to the polylines too
+    assert_eq!(rp.lines_of_type(EdgeType::Visible).count(), 0);
+    assert_eq!(
+        rp.polylines_of_type(EdgeType::Visible).count(),
+        rp.polylines.len()
+    );
+    let triangle: Vec<_> = rp.polylines_of_object(1).collect();
+    assert_eq!(triangle.len(), 1);
+    assert!(triangle[0].is_closed());
+
+    let svg = rp
+        .as_standalone_svg(&SvgOptions::new(100.0, 100.0))
+        .to_string();
+    assert!(svg.contains("<polyline"));
+    assert!(svg.contains("class=\"visible\""));
+}
+
+#[test]
+fn keep_depth_and_w() {
+    let lines = [
+        RenderLine::from_ndc(
+            vec4(0.0, 0.0, 0.1, 1.0),
+            vec4(0.5, 0.0, 0.2, 1.0),
+            EdgeType::Visible,
+        ),
+        RenderLine::from_ndc(
+            vec4(0.5, 0.5, 0.3, 2.0),
+            vec4(0.5, 0.0, 0.2, 1.0),
+            EdgeType::Visible,
+        ),
+    ];
+    let mut polylines = merge_lines(&lines, 1e-5);
+    assert_eq!(polylines.len(), 1);
+    let p = &mut polylines[0];
+    if p.depth()[0] > 0.2 {
+        p.reverse();
+    }
+    assert_eq!(p.depth(), [0.1, 0.2, 0.3]);
+    assert_eq!(p.w(), [1.0, 1.0, 2.0]);
+    assert_eq!(p.ndc_points()[2], vec4(0.5, 0.5, 0.3, 2.0));
+}
+
+#[test]
+fn dedup_before_merge() {
+    let paths = || {
+        let mut rp = RenderPaths::default();
+        for e in [EdgeType::Invisible, EdgeType::Visible].iter().copied() {
+            rp.lines
+                .push(RenderLine::new(vec2(0.0, 0.0), vec2(1.0, 0.0), e));
+        }
+        rp
+    };
+
+    let mut rp = paths();
+    rp.dedup_lines(1e-6, &DEFAULT_PRECEDENCE);
+    rp.merge_lines(1e-6);
+    assert_eq!(rp.polylines.len(), 1);
+    assert_eq!(rp.polylines[0].edge(), EdgeType::Visible);
+
+    // dedup_lines only sees `lines`, which merge_lines has emptied
+    let mut rp = paths();
+    rp.merge_lines(1e-6);
+    rp.dedup_lines(1e-6, &DEFAULT_PRECEDENCE);
+    assert_eq!(rp.polylines.len(), 2);
+}
@@ -0,0 +1,56 @@
+use assert_approx_eq::assert_approx_eq;
+use glm::{look_at, perspective, vec3};
+use nalgebra_glm as glm;
+use vectorfoil::{EdgeType, Renderer};
+
+fn renderer() -> Renderer {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = perspective(1.0, std::f64::consts::FRAC_PI_2, 0.1, 10.0);
+    Renderer::new(&(proj * view))
+}
+
+#[test]
+fn line_depth() {
+    let mut r = renderer();
+    let front = r.add_triangle(
+        vec3(-1.0, -1.0, 1.0),
+        vec3(0.5, 0.0, 1.0),
+        vec3(-1.0, 1.0, 1.0),
+    );
+    r.add_triangle(
+        vec3(-0.5, 0.0, -1.0),
+        vec3(1.0, -1.0, -1.0),
+        vec3(1.0, 1.0, -1.0),
+    );
+    let rp = r.render();
+
+    let front_depth = rp
+        .lines
+        .iter()
+        .filter(|l| l.id() == Some(front))
+        .flat_map(|l| l.depth().to_vec())
+        .fold(f64::NEG_INFINITY, f64::max);
+    let back_depth = rp
+        .lines
+        .iter()
+        .filter(|l| l.id() != Some(front))
+        .flat_map(|l| l.depth().to_vec())
+        .fold(f64::INFINITY, f64::min);
+    assert!(front_depth < back_depth);
+
+    // w is the distance from the eye plane.
+    for l in rp.lines.iter().filter(|l| l.id() == Some(front)) {
+        assert_approx_eq!(l.w()[0], 4.0);
+        assert_approx_eq!(l.w()[1], 4.0);
+        assert_eq!(l.ndc_points()[0].xy(), l.points()[0]);
+    }
+
+    let visible = rp.lines_of_type(EdgeType::Visible).count();
+    let split = rp.lines_of_type(EdgeType::Split).count();
+    assert_eq!(visible + split, rp.lines.len());
+    assert!(split > 0);
+}
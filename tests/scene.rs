@@ -0,0 +1,108 @@
+use glm::{look_at, perspective, translation, vec3};
+use nalgebra_glm as glm;
+use std::sync::Arc;
+use vectorfoil::{Aabb, Attributes, Color, Mesh, Node, Renderer};
+
+fn renderer() -> Renderer {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = perspective(1.0, std::f64::consts::FRAC_PI_2, 0.1, 10.0);
+    Renderer::new(&(proj * view))
+}
+
+fn bolt() -> Arc<Mesh> {
+    let mut mesh = Mesh::new();
+    mesh.add_triangle(
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.5, 0.0, 0.0),
+        vec3(0.0, 0.5, 0.0),
+    );
+    Arc::new(mesh)
+}
+
+#[test]
+fn aabb() {
+    let b = Aabb::from_points(&[vec3(1.0, 0.0, -1.0), vec3(-1.0, 2.0, 0.0)]).unwrap();
+    assert_eq!(b.min, vec3(-1.0, 0.0, -1.0));
+    assert_eq!(b.max, vec3(1.0, 2.0, 0.0));
+    let t = b.transformed(&translation(&vec3(1.0, 1.0, 1.0)));
+    assert_eq!(t.min, vec3(0.0, 1.0, 0.0));
+    assert!(Aabb::from_points(&[]).is_none());
+}
+
+#[test]
+fn nested_transforms() {
+    let mesh = bolt();
+
+    let mut root = Node::new(&translation(&vec3(-1.0, 0.0, 0.0)));
+    root.object = Some(1);
+    root.add_mesh(&mesh);
+    let child = root.add_child(Node::new(&translation(&vec3(1.5, 0.0, 0.0))));
+    child.object = Some(2);
+    child.attributes = Some(Attributes {
+        stroke: Some(Color::rgb(255, 0, 0)),
+        ..Default::default()
+    });
+    child.add_mesh(&mesh);
+
+    let mut r = renderer();
+    let base = r.add_node(root);
+    let rp = r.render();
+
+    let mut expected = renderer();
+    expected.add_instance(&mesh, &translation(&vec3(-1.0, 0.0, 0.0)));
+    expected.add_instance(&mesh, &translation(&vec3(0.5, 0.0, 0.0)));
+    let expected = expected.render();
+
+    assert_eq!(rp.lines.len(), 6);
+    for (a, b) in rp.lines.iter().zip(expected.lines.iter()) {
+        assert!((a.points()[0] - b.points()[0]).norm() < 1e-9);
+        assert!((a.points()[1] - b.points()[1]).norm() < 1e-9);
+    }
+    for l in rp.lines_of_object(2) {
+        assert_eq!(l.id(), Some(base + 1));
+        assert_eq!(
+            rp.line_attributes(l).and_then(|a| a.stroke),
+            Some(Color::rgb(255, 0, 0))
+        );
+    }
+    assert_eq!(rp.lines_of_object(1).count(), 3);
+}
+
+#[test]
+fn subtree_culling() {
+    let mesh = bolt();
+
+    let mut root = Node::default();
+    root.add_mesh(&mesh);
+    let red = Attributes {
+        stroke: Some(Color::rgb(255, 0, 0)),
+        ..Default::default()
+    };
+    // far outside of the view frustum
+    let far = root.add_child(Node::new(&translation(&vec3(100.0, 0.0, 0.0))));
+    far.attributes = Some(red.clone());
+    far.add_mesh(&mesh);
+    far.add_child(Node::default()).add_mesh(&mesh);
+    let hidden = root.add_child(Node::default());
+    hidden.attributes = Some(red);
+    hidden.add_mesh(&mesh);
+    hidden.visible = false;
+
+    let mut r = renderer();
+    let base = r.add_node(root.clone());
+    let rp = r.render();
+    assert_eq!(rp.lines.len(), 3);
+    assert!(rp.lines.iter().all(|l| l.id() == Some(base)));
+
+    // Culled and hidden subtrees are never flattened, so their
+    // attributes are never added to the output. Culling their
+    // primitives one by one would still record them.
+    assert!(rp.attributes.is_empty());
+
+    root.fill_bounds();
+    assert_eq!(root.bounds.unwrap().max.x, 100.5);
+}
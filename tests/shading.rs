@@ -0,0 +1,90 @@
+use assert_approx_eq::assert_approx_eq;
+use glm::{look_at, ortho, vec3};
+use nalgebra_glm as glm;
+use vectorfoil::{Attributes, Color, Light, Renderer, Shading, SvgOptions};
+
+/// Orthographic renderer, looking down the -z axis.
+fn renderer() -> Renderer {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = ortho(-2.0, 2.0, -2.0, 2.0, 0.1, 10.0);
+    Renderer::new(&(proj * view))
+}
+
+#[test]
+fn face_normals() {
+    let mut r = renderer();
+    let facing = r.add_triangle(
+        vec3(-1.0, -1.0, 0.0),
+        vec3(0.0, -1.0, 0.0),
+        vec3(-1.0, 0.0, 0.0),
+    );
+    let tilted = r.add_triangle(
+        vec3(0.5, 0.5, 0.0),
+        vec3(1.5, 0.5, -1.0),
+        vec3(0.5, 1.5, 0.0),
+    );
+    let rp = r.render();
+
+    let face = |id| rp.faces.iter().find(|f| f.id() == Some(id)).unwrap();
+    let n = face(facing).normal().unwrap();
+    assert_approx_eq!(n.z, 1.0);
+    let n = face(tilted).normal().unwrap();
+    assert_approx_eq!(n.x, 0.5f64.sqrt());
+    assert_approx_eq!(n.z, 0.5f64.sqrt());
+}
+
+#[test]
+fn lambert_intensity() {
+    let mut light = Light::new(vec3(0.0, 0.0, 1.0));
+    assert_approx_eq!(light.intensity(&vec3(0.0, 0.0, 1.0)), 1.0);
+    assert_approx_eq!(light.intensity(&vec3(0.0, 0.0, -1.0)), 0.0);
+    let s = 0.5f64.sqrt();
+    assert_approx_eq!(light.intensity(&vec3(s, 0.0, s)), s);
+
+    light.ambient = 0.2;
+    assert_approx_eq!(light.intensity(&vec3(1.0, 0.0, 0.0)), 0.2);
+
+    // no direction, rather than NaN
+    let light = Light::new(vec3(0.0, 0.0, 0.0));
+    assert_eq!(light.direction, vec3(0.0, 0.0, 0.0));
+    assert_eq!(light.intensity(&vec3(0.0, 0.0, -1.0)), 1.0);
+}
+
+#[test]
+fn shaded_svg() {
+    let mut r = renderer();
+    // facing the light
+    r.add_polygon(&[
+        vec3(-1.0, -1.0, 0.0),
+        vec3(0.0, -1.0, 0.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(-1.0, 0.0, 0.0),
+    ]);
+    // tilted 60 degrees away from the light
+    r.set_attributes(Attributes {
+        fill: Some(Color::rgb(200, 100, 0)),
+        ..Default::default()
+    });
+    let h = 3.0f64.sqrt();
+    r.add_polygon(&[
+        vec3(0.5, 0.5, 0.0),
+        vec3(1.0, 0.5, -h / 2.0),
+        vec3(1.0, 1.5, -h / 2.0),
+        vec3(0.5, 1.5, 0.0),
+    ]);
+    let rp = r.render();
+
+    let opt = SvgOptions {
+        faces: true,
+        shading: Some(Shading::new(Light::new(vec3(0.0, 0.0, 1.0)))),
+        ..SvgOptions::new(100.0, 100.0)
+    };
+    let svg = rp.as_standalone_svg(&opt).to_string();
+    assert_eq!(svg.matches("<path").count(), 2);
+    assert!(svg.contains("style=\"fill:#ffffff\""));
+    assert!(svg.contains("style=\"fill:#643200\""));
+}
@@ -0,0 +1,71 @@
+use glm::{look_at, ortho, vec3};
+use nalgebra_glm as glm;
+use vectorfoil::{Attributes, Renderer, StippleOptions, SvgOptions};
+
+/// Orthographic renderer, where NDC = world / 2.
+fn renderer() -> Renderer {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = ortho(-2.0, 2.0, -2.0, 2.0, 0.1, 10.0);
+    Renderer::new(&(proj * view))
+}
+
+fn toned_square(r: &mut Renderer, x: f64, tone: f64) -> usize {
+    r.set_attributes(Attributes {
+        tone: Some(tone),
+        ..Default::default()
+    });
+    r.add_polygon(&[
+        vec3(x, 0.0, 0.0),
+        vec3(x + 1.0, 0.0, 0.0),
+        vec3(x + 1.0, 1.0, 0.0),
+        vec3(x, 1.0, 0.0),
+    ])
+}
+
+#[test]
+fn stipple_density() {
+    let mut r = renderer();
+    toned_square(&mut r, -2.0, 0.0);
+    toned_square(&mut r, 0.0, 0.75);
+    toned_square(&mut r, 1.0, 1.0);
+    let mut rp = r.render();
+    let options = StippleOptions::new(0.02);
+    rp.add_stippling(&options);
+
+    // the squares span x in [-1, -0.5], [0, 0.5] and [0.5, 1] in NDC
+    let count = |lo: f64, hi: f64| {
+        rp.points
+            .iter()
+            .filter(|p| p.point().x > lo && p.point().x < hi)
+            .count()
+    };
+    let (dark, light, white) = (count(-1.0, -0.5), count(0.0, 0.5), count(0.5, 1.0));
+    assert!(dark > 2 * light, "{} {}", dark, light);
+    assert!(light > 0);
+    assert_eq!(white, 0);
+
+    // all dots are inside the faces, and no two are too close
+    for p in rp.points.iter().map(|p| p.point()) {
+        assert!(rp.faces.iter().any(|f| f.contains(p)));
+        assert!(p.y >= 0.0 && p.y <= 0.5);
+    }
+    for (i, p) in rp.points.iter().enumerate() {
+        for q in &rp.points[i + 1..] {
+            assert!((p.point() - q.point()).norm() >= 0.02 - 1e-9);
+        }
+    }
+
+    // the same seed gives the same dots
+    let mut again = r.render();
+    again.add_stippling(&options);
+    assert_eq!(rp.points, again.points);
+
+    let svg = rp
+        .as_standalone_svg(&SvgOptions::new(100.0, 100.0))
+        .to_string();
+    assert_eq!(svg.matches("<circle").count(), rp.points.len());
+}
@@ -0,0 +1,94 @@
+use glm::{look_at, ortho, vec3};
+use nalgebra_glm as glm;
+use vectorfoil::{
+    Color, EdgeType, LineCap, LineStyle, Page, RenderPaths, Renderer, Stylesheet, SvgOptions, Unit,
+};
+
+/// A single visible line from NDC (-0.5, 0) to (0.5, 0).
+fn paths() -> RenderPaths {
+    let view = look_at(
+        &vec3(0.0, 0.0, 5.0),
+        &vec3(0.0, 0.0, 0.0),
+        &vec3(0.0, 1.0, 0.0),
+    );
+    let proj = ortho(-2.0, 2.0, -2.0, 2.0, 0.1, 10.0);
+    let mut r = Renderer::new(&(proj * view));
+    r.add_line(vec3(-1.0, 0.0, 0.0), vec3(1.0, 0.0, 0.0));
+    r.render()
+}
+
+#[test]
+fn output_coordinates() {
+    let svg = paths()
+        .as_standalone_svg(&SvgOptions::new(200.0, 100.0))
+        .to_string();
+    assert!(svg.contains("x1=\"50\""));
+    assert!(svg.contains("x2=\"150\""));
+    assert!(svg.contains("y1=\"50\""));
+    assert!(!svg.contains("transform"));
+
+    // Embedded groups stay in NDC, with y up.
+    let g = paths()
+        .as_svg_group(&SvgOptions::new(200.0, 100.0))
+        .to_string();
+    assert!(g.starts_with("<g transform=\"matrix(0.01 0 0 -0.02 -1 1)\">"));
+    assert!(g.contains("x1=\"50\""));
+}
+
+#[test]
+fn default_styles_in_page_units() {
+    let mut opt = SvgOptions::with_page(Page::new(100.0, 100.0, Unit::In));
+    let scaled = opt.edge_style(EdgeType::Hidden);
+    assert_eq!(scaled.width, 0.5 / 96.0);
+    assert_eq!(scaled.dash, [2.0 / 96.0, 1.0 / 96.0]);
+
+    // without an entry, the fallback is also in inches
+    opt.edge_styles.clear();
+    assert_eq!(opt.edge_style(EdgeType::Hidden), scaled);
+}
+
+#[test]
+fn edge_styles() {
+    let mut opt = SvgOptions::new(100.0, 100.0);
+    opt.edge_styles.insert(
+        EdgeType::Visible,
+        LineStyle {
+            cap: LineCap::Round,
+            ..LineStyle::new(Color::rgb(255, 0, 0), 0.3).dashed(&[1.0, 2.0])
+        },
+    );
+
+    let svg = paths().as_standalone_svg(&opt).to_string();
+    assert!(svg.contains(
+        ".visible { fill: none; stroke: #ff0000; stroke-width: 0.3; \
+         stroke-dasharray: 1 2; stroke-linecap: round; stroke-linejoin: miter; }"
+    ));
+    assert!(svg.contains(".hidden {"));
+    assert!(svg.contains("class=\"visible\""));
+
+    // inline presentation attributes, without a stylesheet
+    opt.inline_styles = true;
+    let svg = paths().as_standalone_svg(&opt).to_string();
+    assert!(!svg.contains("<style"));
+    assert!(svg.contains("stroke=\"#ff0000\""));
+    assert!(svg.contains("stroke-width=\"0.3\""));
+    assert!(svg.contains("stroke-dasharray=\"1 2\""));
+}
+
+#[test]
+fn custom_stylesheets() {
+    let opt = SvgOptions {
+        stylesheet: Stylesheet::Custom(".visible { stroke: blue; }".to_string()),
+        ..SvgOptions::new(100.0, 100.0)
+    };
+    let svg = paths().as_standalone_svg(&opt).to_string();
+    assert!(svg.contains(".visible { stroke: blue; }"));
+    assert!(!svg.contains(".hidden"));
+
+    let opt = SvgOptions {
+        stylesheet: Stylesheet::Link("plot.css".to_string()),
+        ..SvgOptions::new(100.0, 100.0)
+    };
+    let svg = paths().as_standalone_svg(&opt).to_string();
+    assert!(svg.contains("@import url(\"plot.css\");"));
+}
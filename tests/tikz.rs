@@ -0,0 +1,97 @@
+use glm::vec2;
+use nalgebra_glm as glm;
+use vectorfoil::{
+    Color, EdgeType, LineStyle, Marker, MarkerOptions, Page, RenderLine, RenderPaths, RenderPoint,
+    SvgOptions, Unit,
+};
+
+fn tikz(rp: &RenderPaths, options: &SvgOptions) -> String {
+    let mut out = vec![];
+    rp.write_tikz(&mut out, options).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn draw_in_cm() {
+    let mut rp = RenderPaths::default();
+    rp.lines
+        .push(RenderLine::new(vec2(-1.0, -1.0), vec2(1.0, 1.0), EdgeType::Visible));
+    rp.lines
+        .push(RenderLine::new(vec2(-1.0, 1.0), vec2(0.0, 0.0), EdgeType::Hidden));
+    let mut options = SvgOptions::with_page(Page::new(100.0, 50.0, Unit::Mm));
+    options
+        .edge_names
+        .insert(EdgeType::Hidden, "hidden edge".to_string());
+    let out = tikz(&rp, &options);
+
+    assert!(out.starts_with("\\begin{tikzpicture}[\n"));
+    assert!(out.contains("  visible/.style={draw={rgb,255:red,68;green,68;blue,68}, "));
+    assert!(out.contains("  hidden edge/.style={"));
+    assert!(out.contains("dash pattern=on 1.5bp off 0.75bp, line cap=butt, line join=miter},\n"));
+    assert!(out.contains(
+        "]\n\\draw[hidden edge] (0,5) -- (5,2.5);\n\\draw[visible] (0,0) -- (10,5);\n"
+    ));
+    assert!(out.ends_with("\\end{tikzpicture}\n"));
+}
+
+#[test]
+fn closed_polyline_is_a_cycle() {
+    let mut rp = RenderPaths::default();
+    let triangle = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, -1.0)];
+    for w in triangle.windows(2) {
+        rp.lines.push(RenderLine::new(
+            vec2(w[0].0, w[0].1),
+            vec2(w[1].0, w[1].1),
+            EdgeType::Visible,
+        ));
+    }
+    rp.merge_lines(1e-9);
+    let out = tikz(&rp, &SvgOptions::with_page(Page::new(1.0, 1.0, Unit::In)));
+    assert!(out.contains("\\draw[visible] (0,0) -- (2.54,0) -- (2.54,2.54) -- cycle;\n"));
+    assert!(!out.contains("hidden"));
+}
+
+#[test]
+fn odd_dashes_and_style_names() {
+    let mut rp = RenderPaths::default();
+    rp.lines
+        .push(RenderLine::new(vec2(-1.0, 1.0), vec2(0.0, 0.0), EdgeType::Hidden));
+    let mut options = SvgOptions::with_page(Page::new(1.0, 1.0, Unit::In));
+    options.edge_styles.insert(
+        EdgeType::Hidden,
+        LineStyle::new(Color::BLACK, 0.01).dashed(&[0.1, 0.05, 0.02]),
+    );
+    options
+        .edge_names
+        .insert(EdgeType::Hidden, "hidden, back={x}/y".to_string());
+    let out = tikz(&rp, &options);
+
+    assert!(out.contains(
+        "dash pattern=on 7.2bp off 3.6bp on 1.44bp off 7.2bp on 3.6bp off 1.44bp, "
+    ));
+    assert!(out.contains("  hidden- back--x--y/.style={"));
+    assert!(out.contains("\\draw[hidden- back--x--y] (0,2.54) -- (1.27,1.27);\n"));
+}
+
+#[test]
+fn point_markers() {
+    let mut rp = RenderPaths::default();
+    rp.points.push(RenderPoint::new(vec2(0.0, 0.0)));
+    let mut options = SvgOptions::with_page(Page::new(2.0, 2.0, Unit::In));
+    options.markers = MarkerOptions {
+        size: 0.1,
+        color: Color::rgb(255, 0, 0),
+        ..MarkerOptions::default()
+    };
+    let out = tikz(&rp, &options);
+    assert!(out.contains("  point/.style={fill={rgb,255:red,255;green,0;blue,0}},\n"));
+    assert!(out.contains("\\fill[point] (2.54,2.54) circle [radius=0.254];\n"));
+
+    options.markers.shape = Marker::Cross;
+    options.markers.stroke_width = 0.01;
+    let out = tikz(&rp, &options);
+    assert!(out.contains("line width=0.72bp, line cap=butt},\n"));
+    assert!(out.contains(
+        "\\draw[point] (2.286,2.286) -- (2.794,2.794) (2.286,2.794) -- (2.794,2.286);\n"
+    ));
+}
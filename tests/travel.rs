@@ -0,0 +1,82 @@
+use assert_approx_eq::assert_approx_eq;
+use glm::vec2;
+use nalgebra_glm as glm;
+use vectorfoil::{EdgeType, RenderLine, RenderPaths, TravelStats};
+
+/// Rows of short horizontal lines, in a scrambled order and with
+/// mixed directions.
+fn scrambled() -> RenderPaths {
+    let mut rp = RenderPaths::default();
+    for k in 0..40 {
+        let (row, col) = ((k * 7) % 8, (k * 3) % 5);
+        let (x, y) = (-0.8 + col as f64 * 0.3, 0.8 - row as f64 * 0.2);
+        let (a, b) = (vec2(x, y), vec2(x + 0.2, y));
+        let (a, b) = if k % 2 == 0 { (a, b) } else { (b, a) };
+        rp.lines.push(RenderLine::new(a, b, EdgeType::Visible));
+    }
+    rp
+}
+
+#[test]
+fn reduces_travel() {
+    let mut rp = scrambled();
+    let report = rp.optimize_travel();
+    assert_approx_eq!(report.before.draw, 40.0 * 0.2);
+    assert_approx_eq!(report.after.draw, report.before.draw);
+    assert!(report.after.travel < report.before.travel / 2.0);
+    assert!(report.saved() > 0.0);
+    assert_eq!(report.after, TravelStats::of(&rp.polylines));
+
+    assert!(rp.lines.is_empty());
+    assert_eq!(rp.polylines.len(), 40);
+}
+
+#[test]
+fn reverses_strokes() {
+    // two lines, both drawn away from each other
+    let mut rp = RenderPaths::default();
+    rp.lines
+        .push(RenderLine::new(vec2(0.0, 1.0), vec2(-1.0, 1.0), EdgeType::Visible));
+    rp.lines
+        .push(RenderLine::new(vec2(0.0, 1.0), vec2(1.0, 1.0), EdgeType::Visible));
+    let report = rp.optimize_travel();
+    assert_approx_eq!(report.before.travel, 2.0);
+    assert_approx_eq!(report.after.travel, 0.0);
+    assert_eq!(rp.polylines[0].points()[0], vec2(-1.0, 1.0));
+    assert_eq!(rp.polylines[1].points()[1], vec2(1.0, 1.0));
+}
+
+#[test]
+fn many_short_strokes() {
+    // A stipple-like cloud of dots, in a scattered order.
+    let mut rp = RenderPaths::default();
+    for k in 0..2000u64 {
+        let h = k.wrapping_mul(0x9e3779b97f4a7c15);
+        let unit = |bits: u64| (bits & 0xffffff) as f64 / (1u64 << 24) as f64;
+        let p = vec2(unit(h >> 40) * 2.0 - 1.0, unit(h) * 2.0 - 1.0);
+        rp.lines.push(RenderLine::new(p, p + vec2(0.001, 0.0), EdgeType::Visible));
+    }
+    let report = rp.optimize_travel();
+    assert_eq!(rp.polylines.len(), 2000);
+    assert!(report.saved() > 0.0);
+}
+
+#[test]
+fn pen_outside_the_stroke_ends() {
+    // The pen starts at (-1, 1), left of all of the stroke ends. The
+    // end nearest to it is that of `a`, although the row of strokes
+    // along y = 1 starts nearer to the point of their bounding box
+    // closest to the pen.
+    let mut rp = RenderPaths::default();
+    let a = vec2(0.0, 0.9);
+    rp.lines
+        .push(RenderLine::new(a, vec2(0.0, 0.88), EdgeType::Visible));
+    for k in 0..100 {
+        let x = 0.02 + 0.01 * k as f64;
+        rp.lines
+            .push(RenderLine::new(vec2(x, 1.0), vec2(x + 0.005, 1.0), EdgeType::Visible));
+    }
+    let report = rp.optimize_travel();
+    assert_eq!(rp.polylines[0].points()[0], a);
+    assert!(report.after.travel < 2.0, "{:?}", report.after);
+}